@@ -6,6 +6,7 @@ module.exports = {
     "extends": "eslint:recommended",
     "globals": {
         "Atomics": "readonly",
+        "crossOriginIsolated": "readonly",
         "SharedArrayBuffer": "readonly"
     },
     "parserOptions": {
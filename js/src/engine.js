@@ -1,6 +1,8 @@
 import { Universe } from "wasm-game-of-life";
+import { memory } from "wasm-game-of-life/wasm_game_of_life_bg";
 import SimpleEventListener from "./utils/simple_event_listener";
 import { init, render } from "./render";
+import { createSharedTickPool } from "./shared_tick";
 
 /**
  * Run game of life, provide functions to control it and trigger events to
@@ -9,12 +11,32 @@ import { init, render } from "./render";
  * @param {number} heigth
  * @param {number} width
  * @param {number} defaultFps
+ * @param {Object} [sharedTicks] - `workers` running `shared_tick_worker.js`
+ * and the `WebAssembly.Module` of the bindings as `module`, to compute ticks
+ * with those workers when they can share this thread's memory.
  * @returns {Object}
  */
-export default function run(canvas, heigth, width, defaultFps) {
+export default function run(canvas, heigth, width, defaultFps, sharedTicks) {
   const universe = Universe.new(heigth, width);
   const ctx = init(canvas, universe);
 
+  // Ticks are computed on this thread until the workers are ready, or if
+  // they cannot be used (see `createSharedTickPool`).
+  let tickPool = null;
+  if (sharedTicks !== undefined) {
+    createSharedTickPool(sharedTicks.workers, { module: sharedTicks.module, memory })
+      .then(pool => { tickPool = pool; })
+      .catch(err => console.error("Could not compute ticks with workers:", err));
+  }
+
+  function computeNextGeneration() {
+    if (tickPool === null) {
+      universe.tick();
+      return Promise.resolve();
+    }
+    return tickPool.tick(universe);
+  }
+
   let animationId = null;
   let currentFps = defaultFps;
   let timeOfLastTick = performance.now();
@@ -23,23 +45,28 @@ export default function run(canvas, heigth, width, defaultFps) {
 
   const eventListener = SimpleEventListener();
 
+  // Promise of the generation being computed, if any.
+  let pendingTick = null;
+
   function tick() {
     eventListener.triggerEvent("beforeTick");
     let now = performance.now();
-    if (now - timeOfLastTick < (1000 / currentFps)) {
+    if (pendingTick !== null || now - timeOfLastTick < (1000 / currentFps)) {
       animationId = requestAnimationFrame(tick);
       return;
     }
     timeOfLastTick = now;
 
     console.time("tick");
-    universe.tick();
-    console.timeEnd("tick");
-    console.time("render");
-    render(ctx, universe);
-    console.timeEnd("render");
+    pendingTick = computeNextGeneration().then(() => {
+      pendingTick = null;
+      console.timeEnd("tick");
+      console.time("render");
+      render(ctx, universe);
+      console.timeEnd("render");
+      eventListener.triggerEvent("afterTick");
+    });
     animationId = requestAnimationFrame(tick);
-    eventListener.triggerEvent("afterTick");
   }
 
   return {
@@ -71,9 +98,15 @@ export default function run(canvas, heigth, width, defaultFps) {
 
     goToNextFrame() {
       this.pause();
+      if (pendingTick !== null) {
+        // The generation being computed is the next frame.
+        return;
+      }
       timeOfLastTick = performance.now();
-      universe.tick();
-      render(ctx, universe);
+      pendingTick = computeNextGeneration().then(() => {
+        pendingTick = null;
+        render(ctx, universe);
+      });
     },
 
     addEventListener(eventName, eventHandler) {
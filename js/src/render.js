@@ -1,90 +1,92 @@
-import { Cell } from "wasm-game-of-life";
+import { Viewport } from "wasm-game-of-life";
 import { memory } from "wasm-game-of-life/wasm_game_of_life_bg";
 
-const CELL_SIZE = 8; // px
-const GRID_COLOR = "#CCCCCC";
-const DEAD_COLOR = "#FFFFFF";
-const ALIVE_COLOR = "#000000";
+const CELL_SIZE = 8; // px, grid line included
 
+// Colours, as `0xRRGGBBAA` numbers.
+const GRID_COLOR = 0xCCCCCCFF;
+const DEAD_COLOR = 0xFFFFFFFF;
+const ALIVE_COLOR = 0x000000FF;
+const BACKGROUND_COLOR = 0xEEEEEEFF;
+
+let viewport = null;
+
+/**
+ * Prepare the canvas to show the Universe, which can be zoomed with the mouse
+ * wheel and moved by dragging it.
+ * @param {HTMLCanvasElement} canvas
+ * @param {Universe} universe
+ * @returns {CanvasRenderingContext2D}
+ */
 export function init(canvas, universe) {
-  const width = universe.width();
-  const height = universe.height();
+  // Give the canvas room for all of our cells and a 1px border around them,
+  // as long as it fits in the window.
+  canvas.height = Math.min(CELL_SIZE * universe.height() + 1,
+                           window.innerHeight);
+  canvas.width = Math.min(CELL_SIZE * universe.width() + 1,
+                          window.innerWidth);
 
-  // Give the canvas room for all of our cells and a 1px border
-  // around each of them.
-  canvas.height = (CELL_SIZE + 1) * height + 1;
-  canvas.width = (CELL_SIZE + 1) * width + 1;
+  viewport = Viewport.new(canvas.width, canvas.height);
+  viewport.set_grid_color(GRID_COLOR);
+  viewport.set_dead_color(DEAD_COLOR);
+  viewport.set_alive_color(ALIVE_COLOR);
+  viewport.set_background_color(BACKGROUND_COLOR);
 
   const ctx = canvas.getContext('2d');
   render(ctx, universe);
-  canvas.onclick = function (evt) {
-    const columnToUpdate = parseInt(evt.offsetX / (CELL_SIZE + 1), 10);
-    const rowToUpdate = parseInt(evt.offsetY / (CELL_SIZE + 1), 10);
-    universe.toggle_cell(rowToUpdate, columnToUpdate);
+
+  let lastMousePosition = null;
+  let hasDragged = false;
+  canvas.onmousedown = function (evt) {
+    lastMousePosition = { x: evt.offsetX, y: evt.offsetY };
+    hasDragged = false;
+  };
+  canvas.onmousemove = function (evt) {
+    if (lastMousePosition === null) {
+      return;
+    }
+    const dx = evt.offsetX - lastMousePosition.x;
+    const dy = evt.offsetY - lastMousePosition.y;
+    if (dx !== 0 || dy !== 0) {
+      hasDragged = true;
+      viewport.pan(dx, dy);
+      lastMousePosition = { x: evt.offsetX, y: evt.offsetY };
+      render(ctx, universe);
+    }
+  };
+  canvas.onmouseup = canvas.onmouseleave = function () {
+    lastMousePosition = null;
+  };
+  canvas.onwheel = function (evt) {
+    evt.preventDefault();
+    viewport.zoom_at(evt.offsetX, evt.offsetY, evt.deltaY < 0 ? 1 : -1);
     render(ctx, universe);
   };
+  canvas.onclick = function (evt) {
+    if (hasDragged) {
+      return;
+    }
+    const columnToUpdate = viewport.column_at(universe, evt.offsetX);
+    const rowToUpdate = viewport.row_at(universe, evt.offsetY);
+    if (columnToUpdate !== undefined && rowToUpdate !== undefined) {
+      universe.toggle_cell(rowToUpdate, columnToUpdate);
+      render(ctx, universe);
+    }
+  };
   return ctx;
 }
 
+/**
+ * Draw the part of the Universe visible in the canvas, through an image
+ * rendered on the Rust side.
+ * @param {CanvasRenderingContext2D} ctx
+ * @param {Universe} universe
+ */
 export function render(ctx, universe) {
-  const width = universe.width();
-  const height = universe.height();
-  const cellsPtr = universe.get_cells_ptr();
-  drawGrid(ctx, height, width);
-  drawCells(ctx, cellsPtr, height, width);
-}
-
-function drawGrid(ctx, height, width) {
-  ctx.beginPath();
-  ctx.strokeStyle = GRID_COLOR;
-
-  // Vertical lines.
-  for (let i = 0; i <= width; i++) {
-    ctx.moveTo(i * (CELL_SIZE + 1) + 1, 0);
-    ctx.lineTo(i * (CELL_SIZE + 1) + 1, (CELL_SIZE + 1) * height + 1);
-  }
-
-  // Horizontal lines.
-  for (let j = 0; j <= height; j++) {
-    ctx.moveTo(0,                           j * (CELL_SIZE + 1) + 1);
-    ctx.lineTo((CELL_SIZE + 1) * width + 1, j * (CELL_SIZE + 1) + 1);
-  }
-
-  ctx.stroke();
-}
-
-function getIndex(row, column, width) {
-  return row * width + column;
-}
-
-function drawCells(ctx, cellsPtr, height, width) {
-  const cells = new Uint8Array(memory.buffer, cellsPtr, width * height);
-  ctx.beginPath();
-
-  ctx.fillStyle = DEAD_COLOR;
-  for (let row = 0; row < height; row++) {
-    for (let col = 0; col < width; col++) {
-      const idx = getIndex(row, col, width);
-      if (cells[idx] === Cell.Dead) {
-        ctx.fillRect(col * (CELL_SIZE + 1) + 1,
-                     row * (CELL_SIZE + 1) + 1,
-                     CELL_SIZE,
-                     CELL_SIZE);
-      }
-    }
-  }
-
-  ctx.fillStyle = ALIVE_COLOR;
-  for (let row = 0; row < height; row++) {
-    for (let col = 0; col < width; col++) {
-      const idx = getIndex(row, col, width);
-      if (cells[idx] === Cell.Alive) {
-        ctx.fillRect(col * (CELL_SIZE + 1) + 1,
-                     row * (CELL_SIZE + 1) + 1,
-                     CELL_SIZE,
-                     CELL_SIZE);
-      }
-    }
-  }
-  ctx.stroke();
+  viewport.render(universe);
+  const pixels = new Uint8ClampedArray(memory.buffer,
+                                       viewport.pixels_ptr(),
+                                       viewport.pixels_len());
+  const image = new ImageData(pixels, viewport.width(), viewport.height());
+  ctx.putImageData(image, 0, 0);
 }
@@ -0,0 +1,158 @@
+/**
+ * Glue computing the generations of a Universe with workers sharing its
+ * WebAssembly memory, through `Universe.start_shared_tick`,
+ * `run_shared_tick_bands` and `Universe.finish_shared_tick`.
+ *
+ * Workers need the `wasm-game-of-life` bindings built with WebAssembly threads
+ * and the `wasm-threads` feature for the "web" target of wasm-bindgen (see the
+ * `build-wasm-threads` script), so that they can be initialized with the
+ * module and memory of this thread.
+ * When that is not possible, for example because the page is not
+ * cross-origin isolated so that `SharedArrayBuffer` is not available, ticks
+ * are computed on this thread instead.
+ *
+ * Workers can either be Web Workers or Node.js' `Worker`s.
+ */
+
+/**
+ * Returns `true` if ticks can be computed by workers sharing the given
+ * WebAssembly memory: `SharedArrayBuffer` has to be available, which needs
+ * browsers' pages to be cross-origin isolated, and the memory has to be
+ * shared, which needs a build with WebAssembly threads.
+ * @param {WebAssembly.Memory} memory
+ * @returns {boolean}
+ */
+export function canShareTicks(memory) {
+  return typeof SharedArrayBuffer !== "undefined" &&
+    (typeof crossOriginIsolated === "undefined" || crossOriginIsolated) &&
+    memory.buffer instanceof SharedArrayBuffer;
+}
+
+/**
+ * Call `onMessage` with the data of each message received by `target`, which
+ * is either a Web Worker (or its global scope) or a Node.js `Worker` (or its
+ * `parentPort`).
+ * @param {Object} target
+ * @param {Function} onMessage
+ */
+function addMessageListener(target, onMessage) {
+  if (typeof target.on === "function") {
+    target.on("message", onMessage);
+  } else {
+    target.addEventListener("message", evt => onMessage(evt.data));
+  }
+}
+
+/**
+ * Compute ticks with the given workers, which must all call
+ * `runSharedTickWorker`, or on this thread if they cannot share its memory
+ * (see `canShareTicks`), in which case they are terminated.
+ *
+ * @example
+ * ```js
+ * const pool = await createSharedTickPool(workers, { module, memory });
+ * await pool.tick(universe);
+ * ```
+ * @param {Array.<Object>} workers
+ * @param {Object} wasm - The `WebAssembly.Module` and `WebAssembly.Memory`
+ * the bindings were initialized with on this thread.
+ * @returns {Promise.<Object>} - Resolved once every worker is ready.
+ */
+export async function createSharedTickPool(workers, { module, memory }) {
+  if (workers.length === 0 || module === undefined || !canShareTicks(memory)) {
+    for (const worker of workers) {
+      worker.terminate();
+    }
+    return {
+      tick(universe) {
+        universe.tick();
+        return Promise.resolve();
+      },
+      terminate() {},
+    };
+  }
+
+  // Functions returning a promise of the next answer of each worker.
+  const nextAnswers = workers.map(worker => {
+    const waitingAnswers = [];
+    addMessageListener(worker, data => {
+      const resolve = waitingAnswers.shift();
+      if (resolve !== undefined) {
+        resolve(data);
+      }
+    });
+    return () => new Promise(resolve => waitingAnswers.push(resolve));
+  });
+
+  function sendToAll(message) {
+    return Promise.all(workers.map((worker, i) => {
+      const answer = nextAnswers[i]();
+      worker.postMessage(message);
+      return answer.then(data => {
+        if (data.type === "error") {
+          throw new Error(data.message);
+        }
+      });
+    }));
+  }
+
+  await sendToAll({ type: "init", module, memory });
+
+  return {
+    /**
+     * Start computing the next generation of `universe`, which has to come
+     * from the same bindings, and finish it once the workers are done.
+     * The Universe can still be used meanwhile, the generation being then
+     * finished on this thread.
+     * @param {Universe} universe
+     * @param {number} [nbBands] - Number of bands of rows computed in turn by
+     * the workers and this thread.
+     * @returns {Promise}
+     */
+    tick(universe, nbBands = 4 * (workers.length + 1)) {
+      const job = universe.start_shared_tick(nbBands);
+      if (job === 0) {
+        return Promise.resolve();
+      }
+      return sendToAll({ type: "tick", job })
+        .then(() => universe.finish_shared_tick());
+    },
+
+    /**
+     * Stop every worker.
+     */
+    terminate() {
+      for (const worker of workers) {
+        worker.terminate();
+      }
+    },
+  };
+}
+
+/**
+ * Compute the bands of the ticks sent by `createSharedTickPool` through
+ * `port`, which is either a Web Worker's global scope or Node.js'
+ * `parentPort`, with the given bindings.
+ * @param {Object} port
+ * @param {Object} bindings - The `wasm-game-of-life` bindings built for the
+ * "web" target of wasm-bindgen.
+ */
+export function runSharedTickWorker(port, bindings) {
+  addMessageListener(port, data => {
+    switch (data.type) {
+      case "init":
+        try {
+          bindings.initSync({ module: data.module, memory: data.memory });
+          port.postMessage({ type: "ready" });
+        } catch (err) {
+          port.postMessage({ type: "error", message: String(err) });
+        }
+        break;
+      case "tick":
+        // Ticks already finished are ignored by `run_shared_tick_bands`.
+        bindings.run_shared_tick_bands(data.job);
+        port.postMessage({ type: "done" });
+        break;
+    }
+  });
+}
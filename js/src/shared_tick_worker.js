@@ -0,0 +1,6 @@
+// Entry point of the Web Workers given to `createSharedTickPool`, which need
+// the bindings built for the "web" target of wasm-bindgen.
+import * as bindings from "wasm-game-of-life";
+import { runSharedTickWorker } from "./shared_tick";
+
+runSharedTickWorker(self, bindings);
@@ -0,0 +1,85 @@
+/* eslint-env node */
+// Tests for ticks computed by Node.js workers sharing the Universe's memory.
+// This needs the package built by the `build-wasm-threads` script.
+import assert from "assert";
+import { readFile } from "fs/promises";
+import { test } from "node:test";
+import { Worker } from "worker_threads";
+import * as bindings from "../../rs/pkg-threads/wasm_game_of_life.js";
+import { createSharedTickPool } from "../src/shared_tick.js";
+
+const wasmUrl = new URL("../../rs/pkg-threads/wasm_game_of_life_bg.wasm", import.meta.url);
+const workerUrl = new URL("./shared_tick_worker.mjs", import.meta.url);
+
+const module = await WebAssembly.compile(await readFile(wasmUrl));
+const { memory } = bindings.initSync({ module });
+
+/**
+ * Fill a Universe with a pseudo-random soup, always the same for a given
+ * seed, as the Rust tests do.
+ */
+function randomSoup(height, width, seed) {
+  const universe = bindings.Universe.new(height, width);
+  let state = seed;
+  const indices = [];
+  for (let idx = 0; idx < height * width; idx++) {
+    state = (Math.imul(state, 1103515245) + 12345) >>> 0;
+    if ((state >>> 16) & 1) {
+      indices.push(idx);
+    }
+  }
+  universe.set_cells_from_flat(new Uint32Array(indices), bindings.Cell.Alive);
+  return universe;
+}
+
+/** Returns the cells of a Universe, in row then column order. */
+function getCells(universe) {
+  const cells = [];
+  for (let row = 0; row < universe.height(); row++) {
+    for (let col = 0; col < universe.width(); col++) {
+      cells.push(universe.get_cell(row, col));
+    }
+  }
+  return cells;
+}
+
+test("shared ticks computed by workers", async () => {
+  const workers = [0, 1, 2].map(() => new Worker(workerUrl));
+  try {
+    const pool = await createSharedTickPool(workers, { module, memory });
+    const shared = randomSoup(97, 64, 5);
+    const singleThreaded = randomSoup(97, 64, 5);
+    for (let i = 0; i < 30; i++) {
+      await pool.tick(shared);
+      singleThreaded.tick();
+      assert.deepStrictEqual(getCells(shared), getCells(singleThreaded));
+      assert.strictEqual(shared.generation(), singleThreaded.generation());
+    }
+
+    // Updating the Universe while workers compute its tick finishes it.
+    const pending = pool.tick(shared);
+    shared.toggle_cell(3, 3);
+    await pending;
+    singleThreaded.tick();
+    singleThreaded.toggle_cell(3, 3);
+    assert.deepStrictEqual(getCells(shared), getCells(singleThreaded));
+    assert.strictEqual(shared.generation(), singleThreaded.generation());
+  } finally {
+    for (const worker of workers) {
+      worker.terminate();
+    }
+  }
+});
+
+test("ticks computed on this thread without shared memory", async () => {
+  const worker = new Worker(workerUrl);
+  const pool = await createSharedTickPool([worker], {
+    module: undefined,
+    memory: new WebAssembly.Memory({ initial: 1 }),
+  });
+  const universe = randomSoup(20, 20, 3);
+  const singleThreaded = randomSoup(20, 20, 3);
+  await pool.tick(universe);
+  singleThreaded.tick();
+  assert.deepStrictEqual(getCells(universe), getCells(singleThreaded));
+});
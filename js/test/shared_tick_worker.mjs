@@ -0,0 +1,6 @@
+// Worker of the shared ticks' test, running in a Node.js `Worker`.
+import { parentPort } from "worker_threads";
+import * as bindings from "../../rs/pkg-threads/wasm_game_of_life.js";
+import { runSharedTickWorker } from "../src/shared_tick.js";
+
+runSharedTickWorker(parentPort, bindings);
@@ -3,6 +3,11 @@
 extern crate test;
 extern crate wasm_game_of_life;
 
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::random_soup;
+
 #[bench]
 fn universe_ticks(b: &mut test::Bencher) {
     let mut universe = wasm_game_of_life::Universe::new(256,
@@ -12,3 +17,55 @@ fn universe_ticks(b: &mut test::Bencher) {
         universe.tick();
     });
 }
+
+#[bench]
+fn soup_ticks_single_threaded(b: &mut test::Bencher) {
+    let mut universe = random_soup(512, 512, 42);
+    universe.set_active_tiles_tracking(false);
+
+    b.iter(|| {
+        universe.tick();
+    });
+}
+
+#[cfg(feature = "parallel")]
+#[bench]
+fn soup_ticks_parallel(b: &mut test::Bencher) {
+    let mut universe = random_soup(512, 512, 42);
+    universe.set_parallel(true);
+
+    b.iter(|| {
+        universe.tick();
+    });
+}
+
+#[cfg(feature = "simd")]
+#[bench]
+fn soup_ticks_simd(b: &mut test::Bencher) {
+    let mut universe = random_soup(512, 512, 42);
+    universe.set_simd(true);
+
+    b.iter(|| {
+        universe.tick();
+    });
+}
+
+#[bench]
+fn soup_ticks_lookup_table(b: &mut test::Bencher) {
+    let mut universe = random_soup(512, 512, 42);
+    universe.set_tick_engine(wasm_game_of_life::TickEngine::LookupTable);
+
+    b.iter(|| {
+        universe.tick();
+    });
+}
+
+#[bench]
+fn soup_framebuffer_render(b: &mut test::Bencher) {
+    let universe = random_soup(512, 512, 42);
+    let mut framebuffer = wasm_game_of_life::Framebuffer::new();
+
+    b.iter(|| {
+        framebuffer.render(&universe).unwrap();
+    });
+}
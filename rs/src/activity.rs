@@ -0,0 +1,172 @@
+//! Tracking of how long each cell has been alive and of where the Universe
+//! was recently active, for visualisation purposes.
+
+use wasm_bindgen::prelude::*;
+use crate::{cell_index, Cell, Universe};
+
+/// Heat given to a cell when its state changes, before decaying.
+const MAX_HEAT: u8 = 255;
+
+/// Default amount of heat lost by each cell at every tick.
+const DEFAULT_HEAT_DECAY: u8 = 8;
+
+/// Optional history of the cells of a Universe, updated after every tick.
+///
+/// Both buffers contain one value per cell in row then column order, without
+/// any border, so the cell at a given row and column is at
+/// `row * width + column`.
+#[derive(Clone, Debug)]
+pub(crate) struct Activity {
+    /// For each cell, number of consecutive ticks after which it was alive,
+    /// `0` if it is dead. Empty if ages are not tracked.
+    ages: Vec<u16>,
+
+    /// For each cell, heat given when its state last changed, minus the
+    /// decay of every tick since. Empty if the heat map is not tracked.
+    heat: Vec<u8>,
+
+    /// If `true`, `ages` is tracked.
+    track_ages: bool,
+
+    /// If `true`, `heat` is tracked.
+    track_heat: bool,
+
+    /// Amount of heat lost by each cell at every tick.
+    heat_decay: u8,
+}
+
+impl Activity {
+    /// Create a new `Activity` tracking nothing.
+    pub fn new() -> Activity {
+        Activity {
+            ages: Vec::new(),
+            heat: Vec::new(),
+            track_ages: false,
+            track_heat: false,
+            heat_decay: DEFAULT_HEAT_DECAY,
+        }
+    }
+
+    /// Forget the whole history, for a Universe of the given number of
+    /// cells.
+    pub fn reset(&mut self, nb_cells: usize) {
+        self.reset_ages(nb_cells);
+        self.reset_heat(nb_cells);
+    }
+
+    /// Forget the ages of the cells, for a Universe of the given number of
+    /// cells.
+    pub fn reset_ages(&mut self, nb_cells: usize) {
+        self.ages.clear();
+        if self.track_ages {
+            self.ages.resize(nb_cells, 0);
+        }
+    }
+
+    /// Forget the heat of the cells, for a Universe of the given number of
+    /// cells.
+    pub fn reset_heat(&mut self, nb_cells: usize) {
+        self.heat.clear();
+        if self.track_heat {
+            self.heat.resize(nb_cells, 0);
+        }
+    }
+
+    /// Update the history once a generation has been computed, from the
+    /// cells of a Universe of the given dimensions - stored with their ghost
+    /// border - and the indices of those which changed.
+    pub fn update(&mut self, cells: &[Cell], height: u32, width: u32, changed_cells: &[u32]) {
+        if self.track_ages {
+            for row in 0..height {
+                let start = cell_index(width, row, 0);
+                let row_cells = &cells[start..start + width as usize];
+                let first_age = (row * width) as usize;
+                let row_ages = &mut self.ages[first_age..first_age + width as usize];
+                for (age, &cell) in row_ages.iter_mut().zip(row_cells) {
+                    *age = match cell {
+                        Cell::Alive => age.saturating_add(1),
+                        Cell::Dead => 0,
+                    };
+                }
+            }
+        }
+        if self.track_heat {
+            for heat in self.heat.iter_mut() {
+                *heat = heat.saturating_sub(self.heat_decay);
+            }
+            for &idx in changed_cells {
+                self.heat[idx as usize] = MAX_HEAT;
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Enable or disable the tracking of the number of consecutive ticks
+    /// after which each cell was alive. Disabled by default.
+    ///
+    /// Ages start from `0` when enabled.
+    pub fn set_age_tracking(&mut self, enabled: bool) {
+        self.finish_pending_tick();
+        self.activity.track_ages = enabled;
+        self.activity.reset_ages((self.height * self.width) as usize);
+    }
+
+    /// Enable or disable the tracking of a heat map of the cells which
+    /// changed recently. Disabled by default.
+    ///
+    /// A cell whose state changes during a tick gets a heat of `255`, which
+    /// then decreases at every tick (see `set_heat_decay`), leaving trails
+    /// behind moving patterns.
+    pub fn set_heat_tracking(&mut self, enabled: bool) {
+        self.finish_pending_tick();
+        self.activity.track_heat = enabled;
+        self.activity.reset_heat((self.height * self.width) as usize);
+    }
+
+    /// Set the amount of heat, from a maximum of `255`, that each cell loses at
+    /// every tick. Defaults to `8`.
+    pub fn set_heat_decay(&mut self, decay: u8) {
+        self.finish_pending_tick();
+        self.activity.heat_decay = decay;
+    }
+
+    /// Get pointer to the age of the first cell.
+    /// From this pointer, you should expect `height * width` ages as `u16`,
+    /// the age of the cell at a given row and column being at
+    /// `row * width + column`. A cell's age is the number of consecutive
+    /// ticks after which it was alive, `0` meaning that it was dead after the
+    /// last one.
+    ///
+    /// There is no age to read if their tracking is not enabled, see
+    /// `set_age_tracking`.
+    pub fn get_ages_ptr(&self) -> *const u16 {
+        self.activity.ages.as_ptr()
+    }
+
+    /// Get pointer to the heat of the first cell.
+    /// From this pointer, you should expect `height * width` heat values
+    /// from `0` to `255`, the one of the cell at a given row and column being
+    /// at `row * width + column`.
+    ///
+    /// There is no heat to read if the heat map is not enabled, see
+    /// `set_heat_tracking`.
+    pub fn get_heat_ptr(&self) -> *const u8 {
+        self.activity.heat.as_ptr()
+    }
+}
+
+impl Universe {
+    /// Returns the age of every cell, in row then column order, or nothing if
+    /// their tracking is not enabled. See `get_ages_ptr`.
+    pub fn ages(&self) -> &[u16] {
+        &self.activity.ages
+    }
+
+    /// Returns the heat of every cell, in row then column order, or nothing
+    /// if the heat map is not enabled. See `get_heat_ptr`.
+    pub fn heat(&self) -> &[u8] {
+        &self.activity.heat
+    }
+}
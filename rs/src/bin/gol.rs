@@ -0,0 +1,492 @@
+//! Command-line interface running Universes without a browser, to script
+//! experiments:
+//!
+//! ```text
+//! gol run -n 100 --topology bounded --margin 10 glider.rle -o glider_100.rle
+//! gol convert glider.rle -o glider.cells
+//! gol convert metapixel.mc -o metapixel.rle
+//! gol run -n 10 --grid-lines glider.rle -o glider_10.svg
+//! gol stats -n 1000 --rule critters soup.rle
+//! gol run -n 100000 soup.snap -o soup.snap
+//! gol record -n 50 --cell-size 8 --delay 200 glider.rle -o glider.gif
+//! gol play --margin 20 glider.rle
+//! ```
+//!
+//! Run `gol help` for the list of options.
+
+extern crate wasm_game_of_life;
+
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::process;
+use wasm_game_of_life::{
+    Cell, GifPalette, GifRecorder, MargolusPreset, Pattern, Quadtree, SnapshotError, SvgRenderer,
+    Topology, Universe,
+};
+
+const USAGE: &str = "\
+Usage: gol <command> [options] [pattern]
+
+Read a pattern in the RLE, plaintext or Macrocell format, or a snapshot of a
+Universe written by gol, from the given file, or from the standard input if it
+is `-` or missing. Only the smallest rectangle containing the live cells of a
+Macrocell pattern is read. A snapshot keeps its settings unless they are given
+as options.
+
+Commands:
+  run       Compute generations of the pattern and write the last one
+  convert   Write the pattern in another format
+  stats     Print the population, births and deaths of each generation
+  record    Write an animated GIF of the pattern's generations, from the
+            first one to the last computed one
+  play      Run the pattern interactively in the terminal (needs the `tui`
+            feature)
+  help      Print this message
+
+Options:
+  -n, --generations <n>       Number of generations to compute (default: 1)
+  -r, --rule <rule>           life (b3/s23), critters, billiard-ball-machine or
+                              tron: only Life and those Margolus rules are
+                              supported (default: the pattern's rule, else life)
+  -t, --topology <topology>   torus or bounded (default: torus)
+  -m, --margin <cells>        Dead cells added on each side of the pattern
+                              before computing generations (default: 0)
+      --seed <n>              Seed of the stochastic rules and noise
+      --birth-probability <p>
+                              Probability that a cell is born when it should
+      --survival-probability <p>
+                              Probability that a cell survives when it should
+      --noise-rate <p>        Probability that a cell flips after each tick
+  -o, --output <file>         File to write, `-` for the standard output
+                              (default: -)
+  -f, --format <format>       rle, plaintext, macrocell, svg or snapshot
+                              (default: from the output's extension, rle
+                              otherwise)
+      --cell-size <pixels>    Side of the cells of a GIF or SVG image
+                              (default: 4 for GIF, 10 for SVG)
+      --grid-lines            Draw lines between the cells of an image
+      --delay <ms>            Time each generation is shown in a GIF
+                              (default: 100)
+      --palette <palette>     Colours of a GIF: monochrome, age or heat
+                              (default: monochrome)
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Command {
+    Run,
+    Convert,
+    Stats,
+    Record,
+    Play,
+    Help,
+}
+
+/// Rules which can be followed by the Universe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rule {
+    Life,
+    Margolus(MargolusPreset),
+}
+
+impl Rule {
+    /// Returns the rule of the given name, ignoring the case.
+    fn from_name(name: &str) -> Option<Rule> {
+        match name.to_ascii_lowercase().as_str() {
+            "life" | "b3/s23" => Some(Rule::Life),
+            "critters" => Some(Rule::Margolus(MargolusPreset::Critters)),
+            "billiard-ball-machine" =>
+                Some(Rule::Margolus(MargolusPreset::BilliardBallMachine)),
+            "tron" => Some(Rule::Margolus(MargolusPreset::Tron)),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the rule written in RLE files.
+    fn rle_name(self) -> &'static str {
+        match self {
+            Rule::Life => "B3/S23",
+            Rule::Margolus(MargolusPreset::Critters) => "critters",
+            Rule::Margolus(MargolusPreset::BilliardBallMachine) =>
+                "billiard-ball-machine",
+            Rule::Margolus(MargolusPreset::Tron) => "tron",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Rle,
+    Plaintext,
+    Macrocell,
+    Svg,
+    Snapshot,
+}
+
+impl Format {
+    /// Returns the format usually associated to the extension of the given
+    /// file, if any.
+    fn from_path(path: &str) -> Option<Format> {
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "rle" => Some(Format::Rle),
+            "cells" | "txt" => Some(Format::Plaintext),
+            "mc" => Some(Format::Macrocell),
+            "svg" => Some(Format::Svg),
+            "snap" => Some(Format::Snapshot),
+            _ => None,
+        }
+    }
+}
+
+/// What the input contains.
+enum Input {
+    Pattern(Pattern),
+    Snapshot(Box<Universe>),
+}
+
+/// Options given on the command line.
+#[derive(Debug, Default)]
+struct Options {
+    generations: u32,
+    rule: Option<Rule>,
+    topology: Option<Topology>,
+    margin: u32,
+    seed: Option<u32>,
+    birth_probability: Option<f64>,
+    survival_probability: Option<f64>,
+    noise_rate: Option<f64>,
+    input: Option<String>,
+    output: Option<String>,
+    format: Option<Format>,
+    cell_size: Option<u32>,
+    grid_lines: bool,
+    delay: Option<u32>,
+    palette: Option<GifPalette>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, options) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("gol: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(message) = execute(command, &options) {
+        eprintln!("gol: {}", message);
+        process::exit(1);
+    }
+}
+
+/// Parse the arguments given to the program, without its name.
+fn parse_args(args: &[String]) -> Result<(Command, Options), String> {
+    let mut args = args.iter();
+    let command = match args.next().map(String::as_str) {
+        Some("run") => Command::Run,
+        Some("convert") => Command::Convert,
+        Some("stats") => Command::Stats,
+        Some("record") => Command::Record,
+        Some("play") => Command::Play,
+        Some("help") | Some("-h") | Some("--help") => Command::Help,
+        Some(other) => return Err(format!("unknown command `{}`", other)),
+        None => return Err("missing command".to_owned()),
+    };
+
+    let mut options = Options { generations: 1, ..Options::default() };
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next().ok_or_else(|| format!("missing value for `{}`", arg))
+        };
+        match arg.as_str() {
+            "-n" | "--generations" => options.generations = parse_value(arg, value()?)?,
+            "-r" | "--rule" => {
+                let name = value()?;
+                let rule = Rule::from_name(name)
+                    .ok_or_else(|| format!("unknown rule `{}`", name))?;
+                options.rule = Some(rule);
+            }
+            "-t" | "--topology" => {
+                options.topology = match value()?.as_str() {
+                    "torus" => Some(Topology::Torus),
+                    "bounded" => Some(Topology::Bounded),
+                    other => return Err(format!("unknown topology `{}`", other)),
+                }
+            }
+            "-m" | "--margin" => options.margin = parse_value(arg, value()?)?,
+            "--seed" => options.seed = Some(parse_value(arg, value()?)?),
+            "--birth-probability" =>
+                options.birth_probability = Some(parse_value(arg, value()?)?),
+            "--survival-probability" =>
+                options.survival_probability = Some(parse_value(arg, value()?)?),
+            "--noise-rate" => options.noise_rate = Some(parse_value(arg, value()?)?),
+            "-o" | "--output" => options.output = Some(value()?.clone()),
+            "-f" | "--format" => {
+                options.format = match value()?.as_str() {
+                    "rle" => Some(Format::Rle),
+                    "plaintext" => Some(Format::Plaintext),
+                    "macrocell" => Some(Format::Macrocell),
+                    "svg" => Some(Format::Svg),
+                    "snapshot" => Some(Format::Snapshot),
+                    other => return Err(format!("unknown format `{}`", other)),
+                }
+            }
+            "--cell-size" => options.cell_size = Some(parse_value(arg, value()?)?),
+            "--grid-lines" => options.grid_lines = true,
+            "--delay" => options.delay = Some(parse_value(arg, value()?)?),
+            "--palette" => {
+                options.palette = match value()?.as_str() {
+                    "monochrome" => Some(GifPalette::Monochrome),
+                    "age" => Some(GifPalette::Age),
+                    "heat" => Some(GifPalette::Heat),
+                    other => return Err(format!("unknown palette `{}`", other)),
+                }
+            }
+            "-" => options.input = Some(arg.clone()),
+            other if other.starts_with('-') =>
+                return Err(format!("unknown option `{}`", other)),
+            _ if options.input.is_some() =>
+                return Err(format!("unexpected argument `{}`", arg)),
+            _ => options.input = Some(arg.clone()),
+        }
+    }
+    Ok((command, options))
+}
+
+/// Parse the value of the given option.
+fn parse_value<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value.parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, option))
+}
+
+fn execute(command: Command, options: &Options) -> Result<(), String> {
+    if command == Command::Help {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    let bytes = read_input(options.input.as_deref())?;
+    let input = match Universe::from_snapshot(&bytes) {
+        Ok(universe) => Input::Snapshot(Box::new(universe)),
+        Err(SnapshotError::InvalidSignature) => {
+            let text = String::from_utf8(bytes)
+                .map_err(|_| "the input is neither a pattern nor a snapshot".to_owned())?;
+            Input::Pattern(parse_pattern(&text)?)
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+
+    match command {
+        Command::Convert => match (input, output_format(options)) {
+            // Patterns are kept as they are, with their rule.
+            (Input::Pattern(pattern), Format::Rle) |
+            (Input::Pattern(pattern), Format::Plaintext) |
+            (Input::Pattern(pattern), Format::Macrocell) => write_pattern(&pattern, options),
+            (input, _) => {
+                let (universe, rule) = create_universe(input, options)?;
+                write_universe(&universe, rule, options)
+            }
+        },
+        Command::Run => {
+            let (mut universe, rule) = create_universe(input, options)?;
+            for _ in 0..options.generations {
+                universe.tick();
+            }
+            write_universe(&universe, rule, options)
+        }
+        Command::Stats => {
+            let (mut universe, _) = create_universe(input, options)?;
+            let mut stats = String::from("generation\tpopulation\tbirths\tdeaths\n");
+            let mut population = universe.population();
+            stats.push_str(&format!("{}\t{}\t0\t0\n", universe.generation(), population));
+            for _ in 0..options.generations {
+                universe.tick();
+                let births = universe.changed_states().iter()
+                    .filter(|&&state| state == Cell::Alive)
+                    .count();
+                let deaths = universe.changed_states().len() - births;
+                population = universe.population();
+                stats.push_str(&format!("{}\t{}\t{}\t{}\n",
+                                        universe.generation(), population, births, deaths));
+            }
+            write_output(options.output.as_deref(), stats.as_bytes())
+        }
+        Command::Record => {
+            let (mut universe, _) = create_universe(input, options)?;
+            record(&mut universe, options)
+        }
+        Command::Play => {
+            let (mut universe, _) = create_universe(input, options)?;
+            play(&mut universe)
+        }
+        Command::Help => unreachable!(),
+    }
+}
+
+/// Parse a pattern in the RLE, plaintext or Macrocell format, depending on
+/// the header of the text.
+fn parse_pattern(text: &str) -> Result<Pattern, String> {
+    if !text.starts_with("[M2]") {
+        return Pattern::parse(text).map_err(|err| err.to_string());
+    }
+    let quadtree = Quadtree::from_macrocell(text).map_err(|err| err.to_string())?;
+    let (row, column, height, width) = quadtree.bounding_box()
+        .ok_or_else(|| "the pattern does not contain any live cell".to_owned())?;
+    let too_large = |_| "the pattern is too large".to_owned();
+    let height = u32::try_from(height).map_err(too_large)?;
+    let width = u32::try_from(width).map_err(too_large)?;
+    quadtree.to_pattern(row, column, height, width).map_err(|err| err.to_string())
+}
+
+/// Create a Universe from the given input, set up according to the options,
+/// and returns it with the rule it follows if known.
+fn create_universe(input: Input, options: &Options) -> Result<(Universe, Option<Rule>), String> {
+    let (mut universe, rule) = match input {
+        Input::Pattern(pattern) => {
+            let universe = Universe::from_pattern(&pattern).map_err(|err| err.to_string())?;
+            let rule = match (options.rule, pattern.rule()) {
+                (Some(rule), _) => rule,
+                (None, Some(name)) => Rule::from_name(&name)
+                    .ok_or_else(|| format!("unsupported rule `{}`", name))?,
+                (None, None) => Rule::Life,
+            };
+            (universe, Some(rule))
+        }
+        Input::Snapshot(universe) => (*universe, options.rule),
+    };
+    universe.expand(options.margin).map_err(|err| err.to_string())?;
+
+    match rule {
+        Some(Rule::Life) => universe.remove_margolus_rule(),
+        Some(Rule::Margolus(preset)) =>
+            universe.set_margolus_preset(preset).map_err(|err| err.to_string())?,
+        None => {}
+    }
+    if let Some(topology) = options.topology {
+        universe.set_topology(topology);
+    }
+    if let Some(seed) = options.seed {
+        universe.set_seed(seed);
+    }
+    if let Some(probability) = options.birth_probability {
+        universe.set_birth_probability(probability);
+    }
+    if let Some(probability) = options.survival_probability {
+        universe.set_survival_probability(probability);
+    }
+    if let Some(rate) = options.noise_rate {
+        universe.set_noise_rate(rate);
+    }
+    Ok((universe, rule))
+}
+
+/// Write an animated GIF of the generations of the Universe, from the
+/// current one to the last computed one, to the file given by the options.
+fn record(universe: &mut Universe, options: &Options) -> Result<(), String> {
+    let mut recorder = GifRecorder::new();
+    let palette = options.palette.unwrap_or(GifPalette::Monochrome);
+    match palette {
+        GifPalette::Age => universe.set_age_tracking(true),
+        GifPalette::Heat => universe.set_heat_tracking(true),
+        GifPalette::Monochrome => {}
+    }
+    recorder.set_palette(palette);
+    recorder.set_grid_lines(options.grid_lines);
+    if let Some(cell_size) = options.cell_size {
+        recorder.set_cell_size(cell_size);
+    }
+    if let Some(delay) = options.delay {
+        recorder.set_delay(delay);
+    }
+    let first = universe.generation();
+    let last = first.saturating_add(options.generations);
+    match options.output.as_deref() {
+        None | Some("-") => recorder.write(universe, first, last, io::stdout().lock())
+            .map_err(|err| format!("cannot write to the standard output: {}", err)),
+        Some(path) => {
+            let file = fs::File::create(path)
+                .map_err(|err| format!("cannot write `{}`: {}", path, err))?;
+            recorder.write(universe, first, last, BufWriter::new(file))
+                .map_err(|err| format!("cannot write `{}`: {}", path, err))
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn play(universe: &mut Universe) -> Result<(), String> {
+    wasm_game_of_life::tui::play(universe)
+        .map_err(|err| format!("cannot use the terminal: {}", err))
+}
+
+#[cfg(not(feature = "tui"))]
+fn play(_universe: &mut Universe) -> Result<(), String> {
+    Err("`play` needs gol to be built with the `tui` feature".to_owned())
+}
+
+/// Read the whole given file, or the standard input if it is `-` or missing.
+fn read_input(path: Option<&str>) -> Result<Vec<u8>, String> {
+    match path {
+        None | Some("-") => {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)
+                .map_err(|err| format!("cannot read the standard input: {}", err))?;
+            Ok(bytes)
+        }
+        Some(path) => fs::read(path)
+            .map_err(|err| format!("cannot read `{}`: {}", path, err)),
+    }
+}
+
+/// Returns the format in which the output should be written.
+fn output_format(options: &Options) -> Format {
+    options.format
+        .or_else(|| options.output.as_deref().and_then(Format::from_path))
+        .unwrap_or(Format::Rle)
+}
+
+/// Write the pattern in the text format chosen by the options.
+fn write_pattern(pattern: &Pattern, options: &Options) -> Result<(), String> {
+    let text = match output_format(options) {
+        Format::Plaintext => pattern.to_plaintext(),
+        Format::Macrocell => Quadtree::from_pattern(pattern).to_macrocell(),
+        _ => pattern.to_rle(),
+    };
+    write_output(options.output.as_deref(), text.as_bytes())
+}
+
+/// Write the current generation of the Universe, which follows the given
+/// rule if known, in the format chosen by the options.
+fn write_universe(
+    universe: &Universe,
+    rule: Option<Rule>,
+    options: &Options
+) -> Result<(), String> {
+    match output_format(options) {
+        Format::Rle | Format::Plaintext | Format::Macrocell => {
+            let mut pattern = universe.to_pattern();
+            pattern.set_rule(rule.map(|rule| rule.rle_name().to_owned()));
+            write_pattern(&pattern, options)
+        }
+        Format::Svg => {
+            let mut renderer = SvgRenderer::new();
+            if let Some(cell_size) = options.cell_size {
+                renderer.set_cell_size(cell_size);
+            }
+            renderer.set_grid_lines(options.grid_lines);
+            write_output(options.output.as_deref(), renderer.render(universe).as_bytes())
+        }
+        Format::Snapshot => write_output(options.output.as_deref(), &universe.to_snapshot()),
+    }
+}
+
+/// Write the bytes to the given file, or to the standard output if it is `-`
+/// or missing.
+fn write_output(path: Option<&str>, bytes: &[u8]) -> Result<(), String> {
+    match path {
+        None | Some("-") => io::stdout().write_all(bytes)
+            .map_err(|err| format!("cannot write to the standard output: {}", err)),
+        Some(path) => fs::write(path, bytes)
+            .map_err(|err| format!("cannot write `{}`: {}", path, err)),
+    }
+}
@@ -0,0 +1,454 @@
+//! Compression and decompression of data in the zlib format (RFC 1950), which
+//! wraps the DEFLATE algorithm (RFC 1951) and is used by PNG files.
+//!
+//! Compression favours simplicity over ratio: repeated sequences are found
+//! through a hash table of their first three bytes and written with the fixed
+//! Huffman codes of DEFLATE, which is enough for the long runs of identical
+//! pixels of rendered Universes. Decompression supports every kind of block,
+//! so that files written by other programs can be read.
+
+/// Maximum distance back to a repeated sequence.
+const WINDOW_SIZE: usize = 32768;
+
+/// Shortest and longest repeated sequences which can be encoded.
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// Number of bits of the hashes of the sequences' first bytes.
+const HASH_BITS: u32 = 15;
+
+/// Maximum number of previous sequences with the same hash compared to the
+/// current one, bounding the time spent on repetitive data.
+const MAX_CHAIN: usize = 32;
+
+/// Largest number smaller than 65536, modulo of the Adler-32 checksum.
+const ADLER_MODULO: u32 = 65521;
+
+/// Shortest length and number of extra bits of each length code, from the
+/// symbol `257`.
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67,
+    83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5,
+    5, 5, 0,
+];
+
+/// Shortest distance and number of extra bits of each distance code.
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513,
+    769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11,
+    11, 12, 12, 13, 13,
+];
+
+/// Order in which the lengths of the code length codes of a block with
+/// dynamic Huffman codes are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Symbol ending a block.
+const END_OF_BLOCK: u16 = 256;
+
+/// Compress the given bytes into a zlib stream.
+pub(crate) fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter::new();
+    // Deflate with a window of 32K, for a fast compression.
+    out.bytes.extend_from_slice(&[0x78, 0x01]);
+    // A single final block, with fixed Huffman codes.
+    out.write_bits(1, 1);
+    out.write_bits(1, 2);
+
+    let mut matcher = Matcher::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let (best_len, best_distance) = matcher.find(data, pos);
+        if best_len >= MIN_MATCH {
+            let code = LENGTH_BASES.iter().rposition(|&base| usize::from(base) <= best_len).unwrap();
+            out.write_fixed_symbol(257 + code as u16);
+            out.write_bits((best_len - usize::from(LENGTH_BASES[code])) as u32,
+                           u32::from(LENGTH_EXTRA_BITS[code]));
+            let code = DISTANCE_BASES.iter()
+                .rposition(|&base| usize::from(base) <= best_distance).unwrap();
+            out.write_code(code as u16, 5);
+            out.write_bits((best_distance - usize::from(DISTANCE_BASES[code])) as u32,
+                           u32::from(DISTANCE_EXTRA_BITS[code]));
+            for matched in pos..pos + best_len {
+                matcher.insert(data, matched);
+            }
+            pos += best_len;
+        } else {
+            out.write_fixed_symbol(u16::from(data[pos]));
+            matcher.insert(data, pos);
+            pos += 1;
+        }
+    }
+    out.write_fixed_symbol(END_OF_BLOCK);
+
+    let mut bytes = out.finish();
+    bytes.extend_from_slice(&adler32(data).to_be_bytes());
+    bytes
+}
+
+/// Decompress a zlib stream, which should not give more than `max_len` bytes.
+///
+/// Returns `None` if the stream is invalid, truncated, corrupted or too long.
+pub(crate) fn zlib_decompress(data: &[u8], max_len: usize) -> Option<Vec<u8>> {
+    let (&cmf, &flg) = (data.first()?, data.get(1)?);
+    let is_deflate = cmf & 0x0F == 8 && cmf >> 4 <= 7;
+    let has_dictionary = flg & 0x20 != 0;
+    if !is_deflate || has_dictionary || (u16::from(cmf) << 8 | u16::from(flg)) % 31 != 0 {
+        return None;
+    }
+    let mut reader = BitReader::new(&data[2..]);
+    let out = inflate(&mut reader, max_len)?;
+    let checksum = reader.data.get(reader.pos..reader.pos + 4)?;
+    if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) != adler32(&out) {
+        return None;
+    }
+    Some(out)
+}
+
+/// Returns the Adler-32 checksum of the given bytes.
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 bytes is the most which can be added before `b` overflows.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= ADLER_MODULO;
+        b %= ADLER_MODULO;
+    }
+    b << 16 | a
+}
+
+/// Finds the previous occurrences of sequences, through chains of the
+/// positions of those beginning with the same bytes.
+struct Matcher {
+    /// Last position of a sequence of each hash, `usize::MAX` if none.
+    head: Vec<usize>,
+
+    /// For each position in the window, the previous position of a sequence
+    /// of the same hash.
+    previous: Vec<usize>,
+}
+
+impl Matcher {
+    fn new() -> Matcher {
+        Matcher {
+            head: vec![usize::MAX; 1 << HASH_BITS],
+            previous: vec![usize::MAX; WINDOW_SIZE],
+        }
+    }
+
+    /// Remember the sequence beginning at the given position.
+    fn insert(&mut self, data: &[u8], pos: usize) {
+        if pos + MIN_MATCH <= data.len() {
+            let hash = hash(&data[pos..pos + MIN_MATCH]);
+            self.previous[pos % WINDOW_SIZE] = self.head[hash];
+            self.head[hash] = pos;
+        }
+    }
+
+    /// Returns the length and distance of the longest previous occurrence of
+    /// the sequence beginning at the given position which could be found,
+    /// the length being `0` if there is none.
+    fn find(&self, data: &[u8], pos: usize) -> (usize, usize) {
+        let (mut best_len, mut best_distance) = (0, 0);
+        if pos + MIN_MATCH > data.len() {
+            return (best_len, best_distance);
+        }
+        let mut candidate = self.head[hash(&data[pos..pos + MIN_MATCH])];
+        let mut chain = MAX_CHAIN;
+        while candidate != usize::MAX && pos - candidate <= WINDOW_SIZE && chain > 0 {
+            let len = data[pos..].iter()
+                .zip(&data[candidate..])
+                .take(MAX_MATCH)
+                .take_while(|(a, b)| a == b)
+                .count();
+            if len > best_len {
+                best_len = len;
+                best_distance = pos - candidate;
+                if len == MAX_MATCH {
+                    break;
+                }
+            }
+            candidate = self.previous[candidate % WINDOW_SIZE];
+            chain -= 1;
+        }
+        (best_len, best_distance)
+    }
+}
+
+/// Returns the hash of the first bytes of a sequence.
+fn hash(bytes: &[u8]) -> usize {
+    let value = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+    (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Writes values bit by bit, from the least significant bit of each byte.
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_buffer: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter { bytes: Vec::new(), bit_buffer: 0, bit_count: 0 }
+    }
+
+    /// Write the `nb_bits` lowest bits of the value, from the least
+    /// significant one.
+    fn write_bits(&mut self, value: u32, nb_bits: u32) {
+        self.bit_buffer |= value << self.bit_count;
+        self.bit_count += nb_bits;
+        while self.bit_count >= 8 {
+            self.bytes.push(self.bit_buffer as u8);
+            self.bit_buffer >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    /// Write a Huffman code of `nb_bits` bits, which are stored from the most
+    /// significant one.
+    fn write_code(&mut self, code: u16, nb_bits: u32) {
+        let reversed = code.reverse_bits() >> (16 - nb_bits);
+        self.write_bits(u32::from(reversed), nb_bits);
+    }
+
+    /// Write a literal, length or end of block symbol with its fixed Huffman
+    /// code.
+    fn write_fixed_symbol(&mut self, symbol: u16) {
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    /// Returns the written bytes, the last one being completed with zeros.
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push(self.bit_buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Reads values bit by bit, from the least significant bit of each byte.
+struct BitReader<'a> {
+    data: &'a [u8],
+
+    /// Position of the next byte to load in `bit_buffer`.
+    pos: usize,
+    bit_buffer: u32,
+    bit_count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader { data, pos: 0, bit_buffer: 0, bit_count: 0 }
+    }
+
+    /// Read a value of `nb_bits` bits, at most 16, from the least significant
+    /// one.
+    fn bits(&mut self, nb_bits: u32) -> Option<u32> {
+        while self.bit_count < nb_bits {
+            self.bit_buffer |= u32::from(*self.data.get(self.pos)?) << self.bit_count;
+            self.pos += 1;
+            self.bit_count += 8;
+        }
+        let value = self.bit_buffer & ((1 << nb_bits) - 1);
+        self.bit_buffer >>= nb_bits;
+        self.bit_count -= nb_bits;
+        Some(value)
+    }
+
+    /// Skip the remaining bits of the current byte.
+    fn align(&mut self) {
+        self.bit_buffer = 0;
+        self.bit_count = 0;
+    }
+}
+
+/// Canonical Huffman code, described by the length of the code of each
+/// symbol.
+struct Huffman {
+    /// Number of codes of each length, in bits.
+    counts: [u16; 16],
+
+    /// Symbols ordered by their code.
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Build the code from the length of the code of each symbol, `0` if it
+    /// is not used.
+    /// Returns `None` if there are too many codes of some lengths.
+    fn new(lengths: &[u8]) -> Option<Huffman> {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+        counts[0] = 0;
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return None;
+            }
+        }
+
+        let mut offsets = [0u16; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[usize::from(offsets[usize::from(len)])] = symbol as u16;
+                offsets[usize::from(len)] += 1;
+            }
+        }
+        Some(Huffman { counts, symbols })
+    }
+
+    /// Read the next symbol.
+    fn decode(&self, reader: &mut BitReader) -> Option<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return self.symbols.get((index + code - first) as usize).cloned();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        None
+    }
+}
+
+/// Decompress DEFLATE blocks until the last one.
+fn inflate(reader: &mut BitReader, max_len: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let is_final = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let header = reader.data.get(reader.pos..reader.pos + 4)?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                let complement = u16::from_le_bytes([header[2], header[3]]);
+                if len != !complement {
+                    return None;
+                }
+                let start = reader.pos + 4;
+                let stored = reader.data.get(start..start + usize::from(len))?;
+                if out.len() + stored.len() > max_len {
+                    return None;
+                }
+                out.extend_from_slice(stored);
+                reader.pos = start + usize::from(len);
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].iter_mut().for_each(|len| *len = 8);
+                lengths[144..256].iter_mut().for_each(|len| *len = 9);
+                lengths[256..280].iter_mut().for_each(|len| *len = 7);
+                lengths[280..].iter_mut().for_each(|len| *len = 8);
+                let literals = Huffman::new(&lengths)?;
+                let distances = Huffman::new(&[5; 30])?;
+                inflate_block(reader, &literals, &distances, &mut out, max_len)?;
+            }
+            2 => {
+                let (literals, distances) = read_dynamic_codes(reader)?;
+                inflate_block(reader, &literals, &distances, &mut out, max_len)?;
+            }
+            _ => return None,
+        }
+        if is_final {
+            reader.align();
+            return Some(out);
+        }
+    }
+}
+
+/// Read the Huffman codes of a block with dynamic codes.
+fn read_dynamic_codes(reader: &mut BitReader) -> Option<(Huffman, Huffman)> {
+    let nb_literals = reader.bits(5)? as usize + 257;
+    let nb_distances = reader.bits(5)? as usize + 1;
+    let nb_code_lengths = reader.bits(4)? as usize + 4;
+    if nb_literals > 286 || nb_distances > 30 {
+        return None;
+    }
+    let mut code_lengths = [0u8; 19];
+    for &symbol in &CODE_LENGTH_ORDER[..nb_code_lengths] {
+        code_lengths[symbol] = reader.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(nb_literals + nb_distances);
+    while lengths.len() < nb_literals + nb_distances {
+        let (len, repeat) = match code_lengths.decode(reader)? {
+            len @ 0..=15 => (len as u8, 1),
+            16 => (*lengths.last()?, 3 + reader.bits(2)?),
+            17 => (0, 3 + reader.bits(3)?),
+            18 => (0, 11 + reader.bits(7)?),
+            _ => return None,
+        };
+        lengths.extend((0..repeat).map(|_| len));
+    }
+    if lengths.len() != nb_literals + nb_distances || lengths[usize::from(END_OF_BLOCK)] == 0 {
+        return None;
+    }
+    let literals = Huffman::new(&lengths[..nb_literals])?;
+    let distances = Huffman::new(&lengths[nb_literals..])?;
+    Some((literals, distances))
+}
+
+/// Decompress the symbols of a block with Huffman codes until its end.
+fn inflate_block(
+    reader: &mut BitReader,
+    literals: &Huffman,
+    distances: &Huffman,
+    out: &mut Vec<u8>,
+    max_len: usize
+) -> Option<()> {
+    loop {
+        let symbol = literals.decode(reader)?;
+        if symbol < END_OF_BLOCK {
+            if out.len() >= max_len {
+                return None;
+            }
+            out.push(symbol as u8);
+            continue;
+        }
+        if symbol == END_OF_BLOCK {
+            return Some(());
+        }
+
+        let code = usize::from(symbol - 257);
+        let len = usize::from(*LENGTH_BASES.get(code)?) +
+            reader.bits(u32::from(LENGTH_EXTRA_BITS[code]))? as usize;
+        let code = usize::from(distances.decode(reader)?);
+        let distance = usize::from(*DISTANCE_BASES.get(code)?) +
+            reader.bits(u32::from(DISTANCE_EXTRA_BITS[code]))? as usize;
+        if distance > out.len() || out.len() + len > max_len {
+            return None;
+        }
+        let start = out.len() - distance;
+        for i in start..start + len {
+            out.push(out[i]);
+        }
+    }
+}
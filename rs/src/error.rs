@@ -0,0 +1,303 @@
+use std::error::Error;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// Errors which can be returned when creating or updating a `Universe`.
+///
+/// When returned to JavaScript, they are thrown as `Error` objects with a
+/// message describing the problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UniverseError {
+    /// The Universe would have no cell, because either its height or width
+    /// is `0`.
+    EmptyDimension { height: u32, width: u32 },
+
+    /// The Universe would have more cells than what can be indexed.
+    TooLarge { height: u32, width: u32 },
+
+    /// The given row and column are outside the Universe's boundaries.
+    OutOfBounds { row: u32, column: u32, height: u32, width: u32 },
+
+    /// A Margolus rule table was given whose length is not 16 or whose
+    /// blocks are not all in the `0-15` range.
+    InvalidMargolusTable,
+
+    /// A Margolus rule cannot be followed by a Universe with an odd height or
+    /// width, as 2x2 blocks could not partition it.
+    OddDimension { height: u32, width: u32 },
+
+    /// The operation needs at least one live cell.
+    NoLiveCell,
+
+    /// Going back to a previous generation is only possible with a
+    /// reversible Margolus rule.
+    IrreversibleRule,
+
+    /// An image of the Universe would have more pixels than what can be
+    /// indexed.
+    ImageTooLarge { height: u32, width: u32 },
+
+    /// Generations from `first` to `last` cannot be recorded from the
+    /// `current` one, because it is past `first` or `last` comes before
+    /// `first`.
+    InvalidGenerationRange { first: u32, last: u32, current: u32 },
+}
+
+impl fmt::Display for UniverseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UniverseError::EmptyDimension { height, width } =>
+                write!(f, "A Universe cannot have an empty dimension \
+                           (height: {}, width: {})", height, width),
+            UniverseError::TooLarge { height, width } =>
+                write!(f, "A Universe of {}x{} cells is too large",
+                       height, width),
+            UniverseError::OutOfBounds { row, column, height, width } =>
+                write!(f, "Cell at row {} and column {} is out of the \
+                           Universe's boundaries ({}x{})",
+                       row, column, height, width),
+            UniverseError::InvalidMargolusTable =>
+                write!(f, "A Margolus rule table should contain 16 blocks \
+                           from 0 to 15"),
+            UniverseError::OddDimension { height, width } =>
+                write!(f, "A Margolus rule needs an even height and width \
+                           (height: {}, width: {})", height, width),
+            UniverseError::NoLiveCell =>
+                write!(f, "The Universe does not contain any live cell"),
+            UniverseError::IrreversibleRule =>
+                write!(f, "Only a reversible Margolus rule can go back to a \
+                           previous generation"),
+            UniverseError::ImageTooLarge { height, width } =>
+                write!(f, "An image of a Universe of {}x{} cells would be \
+                           too large", height, width),
+            UniverseError::InvalidGenerationRange { first, last, current } =>
+                write!(f, "Generations {} to {} cannot be recorded from \
+                           generation {}", first, last, current),
+        }
+    }
+}
+
+impl Error for UniverseError {}
+
+impl From<UniverseError> for JsValue {
+    fn from(err: UniverseError) -> JsValue {
+        JsError::new(&err.to_string()).into()
+    }
+}
+
+/// Errors which can be returned when reading a pattern file.
+///
+/// Line numbers begin at `1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternError {
+    /// A RLE file does not contain its `x = ..., y = ...` header line.
+    MissingHeader,
+
+    /// The header line of a RLE file cannot be parsed.
+    InvalidHeader { line: u32 },
+
+    /// A character which does not describe a cell was found.
+    UnexpectedCharacter { line: u32, character: char },
+
+    /// Cells are described outside of the pattern's declared dimensions.
+    OutOfBounds { line: u32 },
+
+    /// The pattern would have more cells than what can be indexed.
+    TooLarge,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PatternError::MissingHeader =>
+                write!(f, "The RLE pattern does not have a header line"),
+            PatternError::InvalidHeader { line } =>
+                write!(f, "Invalid RLE header at line {}", line),
+            PatternError::UnexpectedCharacter { line, character } =>
+                write!(f, "Unexpected character {:?} at line {}", character, line),
+            PatternError::OutOfBounds { line } =>
+                write!(f, "Cells out of the pattern's dimensions at line {}", line),
+            PatternError::TooLarge =>
+                write!(f, "The pattern is too large"),
+        }
+    }
+}
+
+impl Error for PatternError {}
+
+impl From<PatternError> for JsValue {
+    fn from(err: PatternError) -> JsValue {
+        JsError::new(&err.to_string()).into()
+    }
+}
+
+/// Errors which can be returned when reading a PNG file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PngError {
+    /// The file does not begin with the signature of PNG files.
+    InvalidSignature,
+
+    /// The file ends before its `IEND` chunk.
+    Truncated,
+
+    /// The checksum of a chunk, whose type is given, does not match its
+    /// content.
+    CorruptChunk { chunk: [u8; 4] },
+
+    /// The `IHDR` chunk is missing or describes an invalid image.
+    InvalidHeader,
+
+    /// Interlaced images are not supported.
+    Interlaced,
+
+    /// The compressed pixels cannot be decoded.
+    InvalidData,
+
+    /// The image cannot be turned into a Universe.
+    Universe(UniverseError),
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PngError::InvalidSignature =>
+                write!(f, "The file is not a PNG image"),
+            PngError::Truncated =>
+                write!(f, "The PNG file is truncated"),
+            PngError::CorruptChunk { chunk } =>
+                write!(f, "The {} chunk of the PNG file is corrupted",
+                       String::from_utf8_lossy(&chunk)),
+            PngError::InvalidHeader =>
+                write!(f, "The header of the PNG file is invalid"),
+            PngError::Interlaced =>
+                write!(f, "Interlaced PNG images are not supported"),
+            PngError::InvalidData =>
+                write!(f, "The pixels of the PNG file cannot be decoded"),
+            PngError::Universe(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for PngError {}
+
+impl From<UniverseError> for PngError {
+    fn from(err: UniverseError) -> PngError {
+        PngError::Universe(err)
+    }
+}
+
+impl From<PngError> for JsValue {
+    fn from(err: PngError) -> JsValue {
+        JsError::new(&err.to_string()).into()
+    }
+}
+
+/// Errors which can be returned when reading a snapshot of a Universe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The data does not begin with the signature of snapshots.
+    InvalidSignature,
+
+    /// The snapshot was written in a version of the format which is not
+    /// supported.
+    UnsupportedVersion { version: u16 },
+
+    /// The snapshot ends before its checksum.
+    Truncated,
+
+    /// The checksum of the snapshot does not match its content, or data
+    /// follows it.
+    CorruptData,
+
+    /// The snapshot has a valid checksum but describes an invalid state.
+    InvalidData,
+
+    /// The Universe of the snapshot cannot be created.
+    Universe(UniverseError),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SnapshotError::InvalidSignature =>
+                write!(f, "The data is not a snapshot of a Universe"),
+            SnapshotError::UnsupportedVersion { version } =>
+                write!(f, "Version {} of the snapshot format is not supported", version),
+            SnapshotError::Truncated =>
+                write!(f, "The snapshot is truncated"),
+            SnapshotError::CorruptData =>
+                write!(f, "The snapshot is corrupted"),
+            SnapshotError::InvalidData =>
+                write!(f, "The snapshot describes an invalid Universe"),
+            SnapshotError::Universe(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+impl From<UniverseError> for SnapshotError {
+    fn from(err: UniverseError) -> SnapshotError {
+        SnapshotError::Universe(err)
+    }
+}
+
+impl From<SnapshotError> for JsValue {
+    fn from(err: SnapshotError) -> JsValue {
+        JsError::new(&err.to_string()).into()
+    }
+}
+
+/// Errors which can be returned when reading a Macrocell file or flattening
+/// a quadtree.
+///
+/// Line numbers begin at `1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MacrocellError {
+    /// The file does not begin with the `[M2]` header.
+    MissingHeader,
+
+    /// A line is neither a comment, a block of 8x8 cells nor a node.
+    InvalidLine { line: u32 },
+
+    /// A node refers to a node which is not defined before it, or whose
+    /// level is not the one below its own.
+    InvalidNode { line: u32 },
+
+    /// The quadtree, or the region to flatten, has more cells than what can
+    /// be indexed.
+    TooLarge,
+
+    /// The flattened region cannot be turned into a Universe.
+    Universe(UniverseError),
+}
+
+impl fmt::Display for MacrocellError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MacrocellError::MissingHeader =>
+                write!(f, "The Macrocell file does not begin with its header"),
+            MacrocellError::InvalidLine { line } =>
+                write!(f, "Invalid Macrocell line at line {}", line),
+            MacrocellError::InvalidNode { line } =>
+                write!(f, "Invalid reference to a node at line {}", line),
+            MacrocellError::TooLarge =>
+                write!(f, "The pattern is too large"),
+            MacrocellError::Universe(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for MacrocellError {}
+
+impl From<UniverseError> for MacrocellError {
+    fn from(err: UniverseError) -> MacrocellError {
+        MacrocellError::Universe(err)
+    }
+}
+
+impl From<MacrocellError> for JsValue {
+    fn from(err: MacrocellError) -> JsValue {
+        JsError::new(&err.to_string()).into()
+    }
+}
@@ -0,0 +1,203 @@
+//! Rendering of a Universe into a buffer of pixels, which JavaScript can
+//! directly draw on a canvas.
+
+use wasm_bindgen::prelude::*;
+use crate::{png, Cell, Universe, UniverseError};
+
+/// Image of a Universe, drawn as squares of `cell_size` pixels of side,
+/// optionally separated by grid lines of one pixel.
+///
+/// The pixels are stored in row then column order, from the top-left corner,
+/// each on four bytes: red, green, blue and alpha. There is no padding
+/// between rows, which is the layout expected by the `ImageData` of a canvas:
+///
+/// ```js
+/// framebuffer.render(universe);
+/// const pixels = new Uint8ClampedArray(memory.buffer,
+///                                      framebuffer.pixels_ptr(),
+///                                      framebuffer.pixels_len());
+/// const image = new ImageData(pixels, framebuffer.width(), framebuffer.height());
+/// ctx.putImageData(image, 0, 0);
+/// ```
+///
+/// With grid lines, the image begins with a line and each cell is followed by
+/// one, so the cell at a given row and column has its top-left pixel at
+/// `(1 + column * (cell_size + 1), 1 + row * (cell_size + 1))`.
+/// Without them, it is at `(column * cell_size, row * cell_size)`.
+#[wasm_bindgen]
+pub struct Framebuffer {
+    /// Number of pixels of the side of a cell.
+    cell_size: u32,
+
+    /// If `true`, cells are separated by lines of one pixel.
+    grid_lines: bool,
+
+    /// Colours of the live cells, dead cells and grid lines, as RGBA bytes.
+    alive_color: [u8; 4],
+    dead_color: [u8; 4],
+    grid_color: [u8; 4],
+
+    /// Width of the image, in pixels.
+    width: u32,
+
+    /// Height of the image, in pixels.
+    height: u32,
+
+    /// RGBA bytes of every pixel, in row then column order.
+    pixels: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Framebuffer {
+    /// Create an empty framebuffer drawing cells of 8 pixels of side with
+    /// grid lines, live cells in black, dead ones in white and grid lines in
+    /// light grey.
+    pub fn new() -> Framebuffer {
+        Framebuffer {
+            cell_size: 8,
+            grid_lines: true,
+            alive_color: [0x00, 0x00, 0x00, 0xFF],
+            dead_color: [0xFF, 0xFF, 0xFF, 0xFF],
+            grid_color: [0xCC, 0xCC, 0xCC, 0xFF],
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Set the number of pixels of the side of each cell, at least `1`.
+    pub fn set_cell_size(&mut self, cell_size: u32) {
+        self.cell_size = cell_size.max(1);
+    }
+
+    /// Enable or disable the lines of one pixel separating cells.
+    pub fn set_grid_lines(&mut self, enabled: bool) {
+        self.grid_lines = enabled;
+    }
+
+    /// Set the colour of the live cells, as a `0xRRGGBBAA` number.
+    pub fn set_alive_color(&mut self, color: u32) {
+        self.alive_color = color.to_be_bytes();
+    }
+
+    /// Set the colour of the dead cells, as a `0xRRGGBBAA` number.
+    pub fn set_dead_color(&mut self, color: u32) {
+        self.dead_color = color.to_be_bytes();
+    }
+
+    /// Set the colour of the grid lines, as a `0xRRGGBBAA` number.
+    pub fn set_grid_color(&mut self, color: u32) {
+        self.grid_color = color.to_be_bytes();
+    }
+
+    /// Returns the width of the last rendered image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the last rendered image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get pointer to the first byte of the last rendered image.
+    /// From this pointer, you should expect `pixels_len()` bytes, see
+    /// `Framebuffer` for their layout.
+    pub fn pixels_ptr(&self) -> *const u8 {
+        self.pixels.as_ptr()
+    }
+
+    /// Returns the number of bytes of the last rendered image, which is
+    /// `width * height * 4`.
+    pub fn pixels_len(&self) -> u32 {
+        self.pixels.len() as u32
+    }
+
+    /// Draw the current generation of the given Universe, resizing the image
+    /// to fit it if needed.
+    ///
+    /// Returns an error and keep the last image if the new one would have more
+    /// bytes than what can be indexed.
+    pub fn render(&mut self, universe: &Universe) -> Result<(), UniverseError> {
+        let line = if self.grid_lines { 1 } else { 0 };
+        let pitch = u64::from(self.cell_size) + line;
+        let width = u64::from(universe.width) * pitch + line;
+        let height = u64::from(universe.height) * pitch + line;
+        if width * height * 4 > u64::from(u32::MAX) {
+            return Err(UniverseError::ImageTooLarge {
+                height: universe.height,
+                width: universe.width,
+            });
+        }
+        self.width = width as u32;
+        self.height = height as u32;
+        self.pixels.resize((width * height * 4) as usize, 0);
+
+        let row_len = self.width as usize * 4;
+        let cell_size = self.cell_size as usize;
+        let mut rows = self.pixels.chunks_exact_mut(row_len);
+        for row in 0..universe.height {
+            if self.grid_lines {
+                fill(rows.next().unwrap(), self.grid_color);
+            }
+
+            // Every line of pixels of a row of cells is the same.
+            let first_line = rows.next().unwrap();
+            let mut pixels = first_line.chunks_exact_mut(4);
+            for col in 0..universe.width {
+                if self.grid_lines {
+                    pixels.next().unwrap().copy_from_slice(&self.grid_color);
+                }
+                let color = match universe.current_cells[universe.get_index(row, col)] {
+                    Cell::Alive => self.alive_color,
+                    Cell::Dead => self.dead_color,
+                };
+                for pixel in pixels.by_ref().take(cell_size) {
+                    pixel.copy_from_slice(&color);
+                }
+            }
+            if let Some(pixel) = pixels.next() {
+                pixel.copy_from_slice(&self.grid_color);
+            }
+            for other_line in rows.by_ref().take(cell_size - 1) {
+                other_line.copy_from_slice(first_line);
+            }
+        }
+        if let Some(last_line) = rows.next() {
+            fill(last_line, self.grid_color);
+        }
+        Ok(())
+    }
+
+    /// Draw the current generation of the given Universe, as `render` does,
+    /// and returns the image as a PNG file.
+    ///
+    /// Returns an error if the image would have more bytes than what can be
+    /// indexed.
+    pub fn render_png(&mut self, universe: &Universe) -> Result<Vec<u8>, UniverseError> {
+        self.render(universe)?;
+        let mut palette = vec![self.dead_color];
+        for &color in &[self.alive_color, self.grid_color] {
+            if !palette.contains(&color) {
+                palette.push(color);
+            }
+        }
+        let indices: Vec<u8> = self.pixels.chunks_exact(4)
+            .map(|pixel| palette.iter().position(|color| color == pixel).unwrap() as u8)
+            .collect();
+        Ok(png::encode(self.width, self.height, &palette, &indices))
+    }
+}
+
+impl Default for Framebuffer {
+    fn default() -> Framebuffer {
+        Framebuffer::new()
+    }
+}
+
+/// Set every pixel of the given RGBA bytes to the same colour.
+fn fill(pixels: &mut [u8], color: [u8; 4]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&color);
+    }
+}
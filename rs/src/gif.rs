@@ -0,0 +1,529 @@
+//! Recording of generations of a Universe as an animated GIF, to show how a
+//! pattern evolves in documentation or bug reports.
+//!
+//! Frames are encoded as soon as they are drawn, so that only the current and
+//! previous frames are kept in memory whatever the number of generations.
+
+use std::io::{self, Write};
+use wasm_bindgen::prelude::*;
+use crate::{Cell, Universe, UniverseError};
+
+/// Largest width and height of a GIF image, in pixels.
+const MAX_SIDE: u64 = u16::MAX as u64;
+
+/// Number of codes after which the LZW compression starts again from a new
+/// dictionary, codes being on at most 12 bits.
+const MAX_CODES: usize = 4096;
+
+/// Colours of the grid lines, dead cells and live cells of
+/// `GifPalette::Monochrome`, as RGB bytes.
+const MONOCHROME_COLORS: [[u8; 3]; 3] = [
+    [0xCC, 0xCC, 0xCC],
+    [0xFF, 0xFF, 0xFF],
+    [0x00, 0x00, 0x00],
+];
+
+/// Colours of the grid lines and dead cells of `GifPalette::Age`, followed by
+/// those of the live cells by age.
+const AGE_COLORS: [[u8; 3]; 8] = [
+    [0x30, 0x30, 0x30],
+    [0x00, 0x00, 0x00],
+    [0xFF, 0xFF, 0xFF],
+    [0xFF, 0xFF, 0x00],
+    [0xFF, 0xAF, 0x00],
+    [0xFF, 0x5F, 0x00],
+    [0xD7, 0x00, 0x00],
+    [0x87, 0x00, 0x00],
+];
+
+/// Maximum age of the live cells drawn with each colour of
+/// `GifPalette::Age`: a cell is drawn with the colour of the first entry whose
+/// maximum age it does not exceed.
+const MAX_AGES: [u16; 6] = [1, 3, 10, 50, 200, u16::MAX];
+
+/// Colours of the grid lines and live cells of `GifPalette::Heat`, followed
+/// by those of the dead cells from the coldest to the hottest.
+const HEAT_COLORS: [[u8; 3]; 10] = [
+    [0x30, 0x30, 0x30],
+    [0xFF, 0xFF, 0xFF],
+    [0x00, 0x00, 0x00],
+    [0x30, 0x00, 0x00],
+    [0x60, 0x00, 0x00],
+    [0x90, 0x10, 0x00],
+    [0xC0, 0x30, 0x00],
+    [0xE0, 0x60, 0x00],
+    [0xF0, 0xA0, 0x00],
+    [0xFF, 0xE0, 0x40],
+];
+
+/// Index of the colour of the grid lines, in every palette.
+const GRID_INDEX: u8 = 0;
+
+/// Colours used to draw the cells in a GIF.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GifPalette {
+    /// Live cells in black on white.
+    Monochrome,
+    /// Live cells coloured from white to dark red as they get older, on
+    /// black. Ages are only known if their tracking is enabled (see
+    /// `Universe::set_age_tracking`), live cells being otherwise white.
+    Age,
+    /// Live cells in white, and dead ones glowing from yellow to black as
+    /// they cool down after a change. The heat map is only known if its
+    /// tracking is enabled (see `Universe::set_heat_tracking`), dead cells
+    /// being otherwise black.
+    Heat,
+}
+
+impl GifPalette {
+    /// Returns the colours of the palette, as RGB bytes.
+    fn colors(self) -> &'static [[u8; 3]] {
+        match self {
+            GifPalette::Monochrome => &MONOCHROME_COLORS,
+            GifPalette::Age => &AGE_COLORS,
+            GifPalette::Heat => &HEAT_COLORS,
+        }
+    }
+
+    /// Returns the index of the colour of the cell at the given row and
+    /// column.
+    fn cell_color(self, universe: &Universe, row: u32, column: u32) -> u8 {
+        let cell = universe.current_cells[universe.get_index(row, column)];
+        let activity_idx = (row * universe.width + column) as usize;
+        match (self, cell) {
+            (GifPalette::Monochrome, Cell::Dead) => 1,
+            (GifPalette::Monochrome, Cell::Alive) => 2,
+            (GifPalette::Age, Cell::Dead) => 1,
+            (GifPalette::Age, Cell::Alive) => {
+                let age = universe.ages().get(activity_idx).copied().unwrap_or(0);
+                2 + MAX_AGES.iter().position(|&max_age| age <= max_age).unwrap() as u8
+            }
+            (GifPalette::Heat, Cell::Alive) => 1,
+            (GifPalette::Heat, Cell::Dead) => {
+                let heat = universe.heat().get(activity_idx).copied().unwrap_or(0);
+                2 + heat / 32
+            }
+        }
+    }
+}
+
+/// Records generations of a Universe as an animated GIF, each generation
+/// being a frame.
+///
+/// Cells are drawn as squares of `cell_size` pixels of side, optionally
+/// separated by grid lines of one pixel, as by a `Framebuffer`. By default,
+/// cells are 4 pixels of side without grid lines, in black on white, and
+/// the animation shows 10 generations per second and loops forever.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct GifRecorder {
+    /// Number of pixels of the side of a cell.
+    cell_size: u32,
+
+    /// If `true`, cells are separated by lines of one pixel.
+    grid_lines: bool,
+
+    palette: GifPalette,
+
+    /// Time during which each frame is shown, in hundredths of a second.
+    delay: u16,
+
+    /// If `true`, the animation starts again after its last frame.
+    looping: bool,
+
+    /// First row, first column, height and width of the part of the Universe
+    /// to record, if not the whole of it.
+    crop: Option<(u32, u32, u32, u32)>,
+}
+
+#[wasm_bindgen]
+impl GifRecorder {
+    /// Create a recorder drawing whole Universes with cells of 4 pixels of
+    /// side, in black on white, and 100 milliseconds per generation.
+    pub fn new() -> GifRecorder {
+        GifRecorder {
+            cell_size: 4,
+            grid_lines: false,
+            palette: GifPalette::Monochrome,
+            delay: 10,
+            looping: true,
+            crop: None,
+        }
+    }
+
+    /// Set the number of pixels of the side of each cell, at least `1`.
+    pub fn set_cell_size(&mut self, cell_size: u32) {
+        self.cell_size = cell_size.max(1);
+    }
+
+    /// Enable or disable the lines of one pixel separating cells.
+    pub fn set_grid_lines(&mut self, enabled: bool) {
+        self.grid_lines = enabled;
+    }
+
+    /// Set the colours used to draw the cells.
+    pub fn set_palette(&mut self, palette: GifPalette) {
+        self.palette = palette;
+    }
+
+    /// Set the time during which each generation is shown, in milliseconds.
+    ///
+    /// GIF files store it in hundredths of a second, to which it is rounded.
+    /// Note that browsers usually show frames of less than 20 milliseconds
+    /// for 100 milliseconds instead.
+    pub fn set_delay(&mut self, milliseconds: u32) {
+        self.delay = (milliseconds.saturating_add(5) / 10).min(u32::from(u16::MAX)) as u16;
+    }
+
+    /// Set whether the animation starts again after its last frame, or
+    /// stays on it.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Only record the part of the Universe of `height` rows and `width`
+    /// columns beginning at the given row and column.
+    /// The parts of that region outside of the Universe are ignored.
+    pub fn set_crop(&mut self, row: u32, column: u32, height: u32, width: u32) {
+        self.crop = Some((row, column, height, width));
+    }
+
+    /// Record the whole Universe again, after a call to `set_crop`.
+    pub fn clear_crop(&mut self) {
+        self.crop = None;
+    }
+
+    /// Compute generations of the Universe up to `last_generation`, and
+    /// returns a GIF file showing those from `first_generation` to
+    /// `last_generation` included. The Universe is left at the last one.
+    ///
+    /// Returns an error and leave the Universe untouched if it is already
+    /// past `first_generation`, if `last_generation` comes before it, or if
+    /// the recorded part of the Universe is empty or too large for a GIF.
+    pub fn record(
+        &self,
+        universe: &mut Universe,
+        first_generation: u32,
+        last_generation: u32
+    ) -> Result<Vec<u8>, UniverseError> {
+        let region = self.region(universe, first_generation, last_generation)?;
+        let mut gif = Vec::new();
+        self.write_region(universe, &region, first_generation, last_generation, &mut gif)
+            .expect("writing to a vector cannot fail");
+        Ok(gif)
+    }
+}
+
+impl GifRecorder {
+    /// Record generations of the Universe as `record` does, writing the GIF
+    /// file to `writer` as frames are computed.
+    ///
+    /// Errors of `record` are returned as errors of the `InvalidInput` kind.
+    pub fn write<W: Write>(
+        &self,
+        universe: &mut Universe,
+        first_generation: u32,
+        last_generation: u32,
+        writer: W
+    ) -> io::Result<()> {
+        let region = self.region(universe, first_generation, last_generation)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        self.write_region(universe, &region, first_generation, last_generation, writer)
+    }
+
+    /// Returns the part of the Universe to record, after checking that the
+    /// given generations can be.
+    fn region(
+        &self,
+        universe: &Universe,
+        first_generation: u32,
+        last_generation: u32
+    ) -> Result<Region, UniverseError> {
+        if first_generation < universe.generation || last_generation < first_generation {
+            return Err(UniverseError::InvalidGenerationRange {
+                first: first_generation,
+                last: last_generation,
+                current: universe.generation,
+            });
+        }
+        let (row, column, height, width) = self.crop
+            .unwrap_or((0, 0, universe.height, universe.width));
+        let first_row = row.min(universe.height);
+        let first_col = column.min(universe.width);
+        let end_row = row.saturating_add(height).min(universe.height);
+        let end_col = column.saturating_add(width).min(universe.width);
+        let (height, width) = (end_row - first_row, end_col - first_col);
+        if height == 0 || width == 0 {
+            return Err(UniverseError::EmptyDimension { height, width });
+        }
+
+        let line = if self.grid_lines { 1 } else { 0 };
+        let pitch = u64::from(self.cell_size) + line;
+        let image_width = u64::from(width) * pitch + line;
+        let image_height = u64::from(height) * pitch + line;
+        if image_width > MAX_SIDE || image_height > MAX_SIDE {
+            return Err(UniverseError::ImageTooLarge { height, width });
+        }
+        Ok(Region {
+            first_row,
+            first_col,
+            height,
+            width,
+            image_width: image_width as u16,
+            image_height: image_height as u16,
+        })
+    }
+
+    fn write_region<W: Write>(
+        &self,
+        universe: &mut Universe,
+        region: &Region,
+        first_generation: u32,
+        last_generation: u32,
+        writer: W
+    ) -> io::Result<()> {
+        while universe.generation != first_generation {
+            universe.tick();
+        }
+        let mut encoder = Encoder::new(writer,
+                                       region.image_width,
+                                       region.image_height,
+                                       self.palette.colors(),
+                                       self.looping)?;
+        let nb_pixels = usize::from(region.image_width) * usize::from(region.image_height);
+        let mut previous = Vec::with_capacity(nb_pixels);
+        let mut pixels = vec![GRID_INDEX; nb_pixels];
+        loop {
+            self.draw(universe, region, &mut pixels);
+            encoder.write_frame(&pixels, &previous, self.delay)?;
+            if universe.generation == last_generation {
+                break;
+            }
+            std::mem::swap(&mut previous, &mut pixels);
+            pixels.resize(nb_pixels, GRID_INDEX);
+            universe.tick();
+        }
+        encoder.finish()
+    }
+
+    /// Draw the current generation of the part of the Universe, as indices
+    /// in the palette. Grid lines are expected to be already drawn.
+    fn draw(&self, universe: &Universe, region: &Region, pixels: &mut [u8]) {
+        let cell_size = self.cell_size as usize;
+        let mut rows = pixels.chunks_exact_mut(usize::from(region.image_width));
+        for row in region.first_row..region.first_row + region.height {
+            if self.grid_lines {
+                rows.next();
+            }
+
+            // Every line of pixels of a row of cells is the same.
+            let first_line = rows.next().unwrap();
+            let mut line_pixels = first_line.iter_mut();
+            for col in region.first_col..region.first_col + region.width {
+                if self.grid_lines {
+                    line_pixels.next();
+                }
+                let color = self.palette.cell_color(universe, row, col);
+                for pixel in line_pixels.by_ref().take(cell_size) {
+                    *pixel = color;
+                }
+            }
+            for other_line in rows.by_ref().take(cell_size - 1) {
+                other_line.copy_from_slice(first_line);
+            }
+        }
+    }
+}
+
+impl Default for GifRecorder {
+    fn default() -> GifRecorder {
+        GifRecorder::new()
+    }
+}
+
+/// Part of a Universe to record, and size of its image.
+struct Region {
+    first_row: u32,
+    first_col: u32,
+    height: u32,
+    width: u32,
+    image_width: u16,
+    image_height: u16,
+}
+
+/// Writes the frames of a GIF file, which all use the same global palette.
+struct Encoder<W: Write> {
+    writer: W,
+    width: u16,
+    height: u16,
+
+    /// Number of bits of the indices of the palette's colours, as given to
+    /// the LZW compression.
+    min_code_size: u8,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Write the header of a GIF file whose frames are `width` by `height`
+    /// pixels and use the given colours, at most 256.
+    fn new(
+        mut writer: W,
+        width: u16,
+        height: u16,
+        colors: &[[u8; 3]],
+        looping: bool
+    ) -> io::Result<Encoder<W>> {
+        // The palette has a power of two of colours, at least 2.
+        let table_bits = (usize::BITS - (colors.len().max(2) - 1).leading_zeros()) as u8;
+
+        writer.write_all(b"GIF89a")?;
+        writer.write_all(&width.to_le_bytes())?;
+        writer.write_all(&height.to_le_bytes())?;
+        // Global palette, of 8 bits per primary colour, without sorting.
+        writer.write_all(&[0xF0 | (table_bits - 1), 0, 0])?;
+        for idx in 0..1 << table_bits {
+            writer.write_all(colors.get(idx).unwrap_or(&[0, 0, 0]))?;
+        }
+        if looping {
+            // Application extension repeating the animation forever.
+            writer.write_all(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+        }
+        Ok(Encoder { writer, width, height, min_code_size: table_bits.max(2) })
+    }
+
+    /// Write a frame of pixels given as indices in the palette, in row then
+    /// column order, shown during `delay` hundredths of a second.
+    ///
+    /// If the previous frame is given, only the smallest rectangle containing
+    /// the pixels which changed since it is written.
+    fn write_frame(&mut self, pixels: &[u8], previous: &[u8], delay: u16) -> io::Result<()> {
+        let width = usize::from(self.width);
+        let (mut left, mut top, mut right, mut bottom) = (0, 0, width, usize::from(self.height));
+        if previous.len() == pixels.len() {
+            let mut changes: Option<(usize, usize, usize, usize)> = None;
+            for (idx, _) in pixels.iter().zip(previous).enumerate().filter(|(_, (a, b))| a != b) {
+                let (x, y) = (idx % width, idx / width);
+                changes = Some(match changes {
+                    None => (x, y, x + 1, y + 1),
+                    Some((left, top, right, bottom)) =>
+                        (left.min(x), top.min(y), right.max(x + 1), bottom.max(y + 1)),
+                });
+            }
+            // An unchanged frame is still written, as a single pixel, to
+            // keep its delay.
+            (left, top, right, bottom) = changes.unwrap_or((0, 0, 1, 1));
+        }
+
+        // Graphic control extension: the frame is left in place for the next
+        // one to be drawn over it.
+        self.writer.write_all(&[0x21, 0xF9, 0x04, 0x04])?;
+        self.writer.write_all(&delay.to_le_bytes())?;
+        self.writer.write_all(&[0x00, 0x00])?;
+
+        // Image descriptor, without local palette nor interlacing.
+        self.writer.write_all(&[0x2C])?;
+        for value in [left, top, right - left, bottom - top] {
+            self.writer.write_all(&(value as u16).to_le_bytes())?;
+        }
+        self.writer.write_all(&[0x00])?;
+
+        let rectangle = pixels.chunks_exact(width)
+            .skip(top)
+            .take(bottom - top)
+            .flat_map(|row| &row[left..right]);
+        let compressed = lzw_compress(self.min_code_size, rectangle.copied());
+        self.writer.write_all(&[self.min_code_size])?;
+        for block in compressed.chunks(255) {
+            self.writer.write_all(&[block.len() as u8])?;
+            self.writer.write_all(block)?;
+        }
+        self.writer.write_all(&[0x00])
+    }
+
+    /// Write the end of the GIF file.
+    fn finish(mut self) -> io::Result<()> {
+        self.writer.write_all(&[0x3B])?;
+        self.writer.flush()
+    }
+}
+
+/// Compress indices of `min_code_size` bits with the variant of LZW used by
+/// GIF files.
+fn lzw_compress(min_code_size: u8, mut indices: impl Iterator<Item = u8>) -> Vec<u8> {
+    let alphabet_len = 1usize << min_code_size;
+    let clear_code = alphabet_len as u16;
+    let end_code = clear_code + 1;
+    let first_code_size = u32::from(min_code_size) + 1;
+
+    // Code of each known sequence followed by each index, `0` if that
+    // sequence is unknown: no sequence can have the code of the first index.
+    let mut dictionary = vec![0u16; MAX_CODES * alphabet_len];
+    let mut next_code = end_code + 1;
+    let mut code_size = first_code_size;
+    let mut output = BitWriter::default();
+    output.write(clear_code, code_size);
+
+    let mut sequence = match indices.next() {
+        Some(index) => u16::from(index),
+        None => {
+            output.write(end_code, code_size);
+            return output.finish();
+        }
+    };
+    for index in indices {
+        let entry = usize::from(sequence) * alphabet_len + usize::from(index);
+        if dictionary[entry] != 0 {
+            sequence = dictionary[entry];
+            continue;
+        }
+        output.write(sequence, code_size);
+        // Decoders use one more bit once the dictionary has as many codes as
+        // can be written, which they only know after reading a code.
+        if usize::from(next_code) == MAX_CODES {
+            output.write(clear_code, code_size);
+            dictionary.iter_mut().for_each(|code| *code = 0);
+            next_code = end_code + 1;
+            code_size = first_code_size;
+        } else {
+            if next_code == 1 << code_size {
+                code_size += 1;
+            }
+            dictionary[entry] = next_code;
+            next_code += 1;
+        }
+        sequence = u16::from(index);
+    }
+    output.write(sequence, code_size);
+    if next_code == 1 << code_size && usize::from(next_code) < MAX_CODES {
+        code_size += 1;
+    }
+    output.write(end_code, code_size);
+    output.finish()
+}
+
+/// Packs codes of variable sizes into bytes, from their least significant bit.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    nb_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u32) {
+        self.buffer |= u32::from(code) << self.nb_bits;
+        self.nb_bits += size;
+        while self.nb_bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.nb_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.nb_bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
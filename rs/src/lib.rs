@@ -1,4 +1,53 @@
+mod activity;
+mod crc;
+mod deflate;
+mod error;
+mod framebuffer;
+mod gif;
+mod lookup_table;
+mod macrocell;
+mod margolus;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod pattern;
+mod png;
+mod region;
+mod rng;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "wasm-threads")]
+mod shared_tick;
+mod snapshot;
+#[cfg(feature = "simd")]
+mod simd;
+mod svg;
+mod terminal;
+mod text;
+mod tiles;
+#[cfg(feature = "tui")]
+pub mod tui;
 mod utils;
+mod viewport;
+
+pub use error::{MacrocellError, PatternError, PngError, SnapshotError, UniverseError};
+pub use framebuffer::Framebuffer;
+pub use gif::{GifPalette, GifRecorder};
+pub use macrocell::Quadtree;
+pub use margolus::MargolusRule;
+pub use pattern::Pattern;
+pub use region::PasteMode;
+#[cfg(feature = "wasm-threads")]
+pub use shared_tick::run_shared_tick_bands;
+#[cfg(feature = "simd")]
+pub use simd::simd_compiled;
+pub use svg::SvgRenderer;
+pub use terminal::{ColorMode, TerminalRenderer};
+pub use text::{TextMode, TextRenderer};
+pub use viewport::Viewport;
+use activity::Activity;
+use lookup_table::LookupTable;
+use rng::Rng;
+use tiles::{ActiveTiles, TILE_SIZE};
 
 use std::fmt;
 use wasm_bindgen::prelude::*;
@@ -11,18 +60,137 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 #[wasm_bindgen]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Cell {
     Dead = 0,
     Alive = 1,
 }
 
+/// Margolus rules which can be directly set on a `Universe`.
+#[wasm_bindgen]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MargolusPreset {
+    Critters,
+    BilliardBallMachine,
+    Tron,
+}
+
+/// Algorithms computing the generations of a Universe following the Game of
+/// Life's rules.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickEngine {
+    /// Count the live neighbours of each cell.
+    Loop,
+    /// Look the next state of each cell up in a table precomputed for every
+    /// possible 3x3 neighbourhood.
+    LookupTable,
+}
+
+/// How the edges of a Universe are connected, when following the Game of
+/// Life's rules.
+#[wasm_bindgen]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Each edge is connected to the opposite one, as on a torus.
+    Torus,
+    /// Cells outside of the Universe are considered dead.
+    Bounded,
+}
+
+/// Part of a Universe which stays in place when it is resized.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
 #[wasm_bindgen]
 pub struct Universe {
     width: u32,
     height: u32,
+
+    /// Cells of the current generation, in row then column order, surrounded
+    /// by a ghost border of one cell on each side (see `stride`).
+    /// That border contains a copy of the opposite edges of the Universe
+    /// while computing a generation, so that the neighbours of every cell
+    /// can be read without wrapping.
     current_cells: Vec<Cell>,
+
+    /// Buffer where the next generation is computed, organized the same way
+    /// than `current_cells`.
     next_cells: Vec<Cell>,
+
+    /// How the edges of the Universe are connected.
+    topology: Topology,
+
+    /// Number of generations computed since the Universe was created.
+    /// Decremented when going back in time through `reverse_tick`.
+    generation: u32,
+
+    /// If set, the Universe evolves as a Margolus block cellular automaton
+    /// following that rule, instead of the Game of Life's rules.
+    margolus_rule: Option<MargolusRule>,
+
+    /// Pseudo-random number generator used by the stochastic rules and the
+    /// noise, so a noisy run can be reproduced from its seed.
+    rng: Rng,
+
+    /// Probability that a dead cell which should be born according to the
+    /// Game of Life's rules actually becomes alive.
+    birth_probability: f64,
+
+    /// Probability that a live cell which should survive according to the
+    /// Game of Life's rules actually stays alive.
+    survival_probability: f64,
+
+    /// Probability for each cell to be flipped at the end of every tick.
+    noise_rate: f64,
+
+    /// Indices of the cells whose state changed during the last tick.
+    changed_cells: Vec<u32>,
+
+    /// New state of each cell in `changed_cells`, at the same position.
+    changed_states: Vec<Cell>,
+
+    /// Ages of the cells and heat map of the recent changes, if tracked.
+    activity: Activity,
+
+    /// Parts of the Universe which changed during the last generation.
+    active_tiles: ActiveTiles,
+
+    /// If `true`, only the parts of the Universe around cells which changed
+    /// during the last generation are computed, when possible.
+    track_active_tiles: bool,
+
+    /// Next state of a cell for each possible neighbourhood, if the lookup
+    /// table engine is selected.
+    lookup_table: Option<LookupTable>,
+
+    /// If `true`, generations are computed on multiple threads when
+    /// possible.
+    #[cfg(feature = "parallel")]
+    parallel: bool,
+
+    /// Generation currently computed by multiple workers, if one.
+    #[cfg(feature = "wasm-threads")]
+    shared_tick: Option<Box<shared_tick::SharedTick>>,
+
+    /// If `true`, generations are computed through SIMD instructions when
+    /// possible.
+    #[cfg(feature = "simd")]
+    simd: bool,
 }
 
 #[wasm_bindgen]
@@ -30,16 +198,52 @@ impl Universe {
     /// Create a new Game Of Life's Universe with the corresponding height
     /// (number of rows) and width (number of columns).
     /// All Cells are initialized to a `Cell::Dead` state at the beginning.
+    ///
+    /// Panics if the Universe cannot be created, see `try_new` for a version
+    /// returning an error instead.
     pub fn new(height: u32, width: u32) -> Universe {
+        match Universe::try_new(height, width) {
+            Ok(universe) => universe,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Create a new Game Of Life's Universe with the corresponding height
+    /// (number of rows) and width (number of columns).
+    /// All Cells are initialized to a `Cell::Dead` state at the beginning.
+    ///
+    /// Returns an error if one of the dimensions is `0` or if there would be
+    /// too many cells.
+    pub fn try_new(height: u32, width: u32) -> Result<Universe, UniverseError> {
         utils::set_panic_hook();
-        let current_cells = vec![Cell::Dead; (width * height) as usize];
-        let next_cells = vec![Cell::Dead; (width * height) as usize];
-        Universe {
+        let nb_cells = check_dimensions(height, width)?;
+        let current_cells = vec![Cell::Dead; nb_cells];
+        let next_cells = vec![Cell::Dead; nb_cells];
+        Ok(Universe {
             width,
             height,
             current_cells,
             next_cells,
-        }
+            topology: Topology::Torus,
+            generation: 0,
+            margolus_rule: None,
+            rng: Rng::new(0),
+            birth_probability: 1.,
+            survival_probability: 1.,
+            noise_rate: 0.,
+            changed_cells: Vec::new(),
+            changed_states: Vec::new(),
+            activity: Activity::new(),
+            active_tiles: ActiveTiles::new(height, width),
+            track_active_tiles: true,
+            lookup_table: None,
+            #[cfg(feature = "parallel")]
+            parallel: false,
+            #[cfg(feature = "wasm-threads")]
+            shared_tick: None,
+            #[cfg(feature = "simd")]
+            simd: false,
+        })
     }
 
     /// Toggle (alternate between `Cell::Alive` and `Cell::Dead`) a given cell
@@ -47,12 +251,167 @@ impl Universe {
     /// If no cell is found at that row and column (because it goes further than
     /// the cells boundaries), do nothing.
     pub fn toggle_cell(&mut self, row : u32, column : u32) {
+        self.finish_pending_tick();
         if let Some(cell) = self.get_cell_mut(row, column) {
             (*cell) = match cell {
                 Cell::Dead => Cell::Alive,
                 Cell::Alive => Cell::Dead
             };
+            self.active_tiles.mark_cell(row, column);
+        }
+    }
+
+    /// Toggle (alternate between `Cell::Alive` and `Cell::Dead`) a given cell
+    /// corresponding to the row and column given.
+    /// Returns an error if no cell is found at that row and column.
+    pub fn try_toggle_cell(
+        &mut self,
+        row: u32,
+        column: u32
+    ) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        self.toggle_cell(row, column);
+        Ok(())
+    }
+
+    /// Returns the state of the cell at the given row and column.
+    /// Returns an error if no cell is found at that row and column.
+    pub fn get_cell(&self, row: u32, column: u32) -> Result<Cell, UniverseError> {
+        self.check_bounds(row, column)?;
+        Ok(self.current_cells[self.get_index(row, column)])
+    }
+
+    /// Set the state of the cell at the given row and column, whatever its
+    /// current state is.
+    /// Returns an error if no cell is found at that row and column.
+    pub fn set_cell(
+        &mut self,
+        row: u32,
+        column: u32,
+        state: Cell
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.check_bounds(row, column)?;
+        let idx = self.get_index(row, column);
+        self.current_cells[idx] = state;
+        self.active_tiles.mark_cell(row, column);
+        Ok(())
+    }
+
+    /// Set the state of multiple cells at once, by giving their index as
+    /// `row * width + column` (as in the list of changed cells).
+    ///
+    /// Returns an error without updating any cell if one of the indices is
+    /// out of the Universe's boundaries.
+    pub fn set_cells_from_flat(
+        &mut self,
+        indices: &[u32],
+        state: Cell
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        let nb_cells = self.height * self.width;
+        if let Some(&idx) = indices.iter().find(|&&idx| idx >= nb_cells) {
+            return Err(UniverseError::OutOfBounds {
+                row: idx / self.width,
+                column: idx % self.width,
+                height: self.height,
+                width: self.width,
+            });
+        }
+        for &idx in indices {
+            let (row, column) = (idx / self.width, idx % self.width);
+            let cell_idx = self.get_index(row, column);
+            self.current_cells[cell_idx] = state;
+            self.active_tiles.mark_cell(row, column);
+        }
+        Ok(())
+    }
+
+    /// Set every cell of the Universe to `Cell::Dead`.
+    pub fn clear(&mut self) {
+        self.finish_pending_tick();
+        for cell in self.current_cells.iter_mut() {
+            *cell = Cell::Dead;
+        }
+        self.active_tiles.invalidate();
+    }
+
+    /// Change the height and width of the Universe while keeping its current
+    /// cells, the `anchor` indicating which part of the Universe stays in
+    /// place.
+    /// Cells which do not fit anymore are lost and new cells are dead.
+    ///
+    /// Returns an error and do nothing if the new dimensions are not valid.
+    pub fn resize(
+        &mut self,
+        new_height: u32,
+        new_width: u32,
+        anchor: Anchor
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        let row_offset = match anchor {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => 0,
+            Anchor::Left | Anchor::Center | Anchor::Right =>
+                (i64::from(new_height) - i64::from(self.height)) / 2,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight =>
+                i64::from(new_height) - i64::from(self.height),
+        };
+        let column_offset = match anchor {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => 0,
+            Anchor::Top | Anchor::Center | Anchor::Bottom =>
+                (i64::from(new_width) - i64::from(self.width)) / 2,
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight =>
+                i64::from(new_width) - i64::from(self.width),
+        };
+        self.reframe(new_height, new_width, row_offset, column_offset)
+    }
+
+    /// Shrink the Universe to the smallest rectangle containing all of its
+    /// live cells, with `margin` dead cells added on each side.
+    ///
+    /// Returns an error and do nothing if there is no live cell or if the new
+    /// dimensions are not valid.
+    pub fn crop_to_bounding_box(&mut self, margin: u32) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.current_cells[self.get_index(row, col)] == Cell::Dead {
+                    continue;
+                }
+                bounds = Some(match bounds {
+                    None => (row, row, col, col),
+                    Some((top, bottom, left, right)) =>
+                        (top.min(row), bottom.max(row), left.min(col), right.max(col)),
+                });
+            }
         }
+        let (top, bottom, left, right) = match bounds {
+            Some(bounds) => bounds,
+            None => return Err(UniverseError::NoLiveCell),
+        };
+        let new_height = (u64::from(bottom - top) + 1 + 2 * u64::from(margin))
+            .min(u64::from(u32::MAX)) as u32;
+        let new_width = (u64::from(right - left) + 1 + 2 * u64::from(margin))
+            .min(u64::from(u32::MAX)) as u32;
+        self.reframe(new_height,
+                     new_width,
+                     i64::from(margin) - i64::from(top),
+                     i64::from(margin) - i64::from(left))
+    }
+
+    /// Add `margin` dead cells on each side of the Universe, to give room to
+    /// its pattern.
+    ///
+    /// Returns an error and do nothing if the new dimensions are not valid.
+    pub fn expand(&mut self, margin: u32) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        let new_height = self.height.saturating_add(margin.saturating_mul(2));
+        let new_width = self.width.saturating_add(margin.saturating_mul(2));
+        self.reframe(new_height,
+                     new_width,
+                     i64::from(margin),
+                     i64::from(margin))
     }
 
     /// Returns the width of the Universe's cells, which can also be considered
@@ -67,36 +426,291 @@ impl Universe {
         self.height
     }
 
+    /// Returns the number of generations computed since this Universe was
+    /// created.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the number of live cells of the current generation.
+    pub fn population(&self) -> u32 {
+        (0..self.height)
+            .map(|row| {
+                let start = self.get_index(row, 0);
+                self.current_cells[start..start + self.width as usize]
+                    .iter()
+                    .filter(|&&cell| cell == Cell::Alive)
+                    .count() as u32
+            })
+            .sum()
+    }
+
+    /// Returns the number of cells between the beginning of two consecutive
+    /// rows in the buffer returned by `get_cells_ptr`, which is larger than
+    /// the width as cells are stored with a border around them.
+    pub fn stride(&self) -> u32 {
+        self.width + 2
+    }
+
     /// Get pointer to the first cell in memory from this Universe.
-    /// From this pointer, you should expect height*width Cells, organized in a
-    /// row, then column order.
+    /// From this pointer, you should expect `height` rows of `width` Cells,
+    /// each row beginning `stride()` Cells after the previous one. The cell
+    /// at a given row and column is thus at `row * stride + column`.
     pub fn get_cells_ptr(&self) -> *const Cell {
-        self.current_cells.as_ptr()
+        self.current_cells[self.get_index(0, 0)..].as_ptr()
+    }
+
+    /// Make the Universe follow the Margolus block rule described by the given
+    /// 16-entry table instead of the Game of Life's rules.
+    /// See `MargolusRule` for how blocks are encoded.
+    ///
+    /// Returns an error and keep the current rule if the table is invalid or
+    /// if the Universe has an odd width or height, as 2x2 blocks could not
+    /// then partition it.
+    pub fn set_margolus_rule(&mut self, table: &[u8]) -> Result<(), UniverseError> {
+        match MargolusRule::new(table) {
+            Some(rule) => self.set_margolus(rule),
+            None => Err(UniverseError::InvalidMargolusTable),
+        }
+    }
+
+    /// Make the Universe follow one of the known Margolus rules instead of the
+    /// Game of Life's rules.
+    ///
+    /// Returns an error and keep the current rule if the Universe has an odd
+    /// width or height.
+    pub fn set_margolus_preset(
+        &mut self,
+        preset: MargolusPreset
+    ) -> Result<(), UniverseError> {
+        let rule = match preset {
+            MargolusPreset::Critters => MargolusRule::critters(),
+            MargolusPreset::BilliardBallMachine =>
+                MargolusRule::billiard_ball_machine(),
+            MargolusPreset::Tron => MargolusRule::tron(),
+        };
+        self.set_margolus(rule)
+    }
+
+    /// Go back to the Game of Life's rules, if a Margolus rule was set.
+    pub fn remove_margolus_rule(&mut self) {
+        self.finish_pending_tick();
+        self.margolus_rule = None;
+        self.active_tiles.invalidate();
+    }
+
+    /// Get pointer to the indices of the cells which changed during the last
+    /// tick, in the same row then column order than in `get_cells_ptr`.
+    /// From this pointer, you should expect `changed_cells_len` u32 values.
+    ///
+    /// Only changes made by `tick` and `reverse_tick` are listed. Cells
+    /// updated through other methods, such as `toggle_cell`, are not.
+    pub fn get_changed_cells_ptr(&self) -> *const u32 {
+        self.changed_cells.as_ptr()
+    }
+
+    /// Get pointer to the new state of the cells which changed during the last
+    /// tick, in the same order than in `get_changed_cells_ptr`: `Cell::Alive`
+    /// if it was born and `Cell::Dead` if it died.
+    /// From this pointer, you should expect `changed_cells_len` Cells.
+    pub fn get_changed_states_ptr(&self) -> *const Cell {
+        self.changed_states.as_ptr()
+    }
+
+    /// Returns the number of cells which changed during the last tick.
+    pub fn changed_cells_len(&self) -> u32 {
+        self.changed_cells.len() as u32
+    }
+
+    /// Enable or disable the tracking of the parts of the Universe which
+    /// changed, allowing `tick` to skip the stable ones. Enabled by default.
+    ///
+    /// This has no effect on the result, only on performance. Note that
+    /// stable parts are only skipped when following the Game of Life's rules,
+    /// without stochastic transitions nor noise, with the `TickEngine::Loop`
+    /// engine. Parallel and SIMD computations, when enabled, and the lookup
+    /// table engine take precedence and always compute every cell.
+    pub fn set_active_tiles_tracking(&mut self, enabled: bool) {
+        self.finish_pending_tick();
+        self.track_active_tiles = enabled;
+    }
+
+    /// Set how the edges of the Universe are connected. Defaults to
+    /// `Topology::Torus`.
+    ///
+    /// Note that Margolus rules always wrap around the edges, whatever the
+    /// topology.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.finish_pending_tick();
+        self.topology = topology;
+        self.active_tiles.invalidate();
+    }
+
+    /// Returns how the edges of the Universe are connected.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Select the algorithm computing the generations when following the Game
+    /// of Life's rules. Defaults to `TickEngine::Loop`.
+    ///
+    /// This has no effect on the result, only on performance. Note that the
+    /// lookup table engine is only used without stochastic transitions nor
+    /// noise, and when neither parallel nor SIMD computations are enabled.
+    /// It then takes precedence over the tracking of active tiles, as it
+    /// always computes every cell.
+    pub fn set_tick_engine(&mut self, engine: TickEngine) {
+        self.finish_pending_tick();
+        self.lookup_table = match engine {
+            TickEngine::Loop => None,
+            TickEngine::LookupTable => Some(LookupTable::new(life_next_state)),
+        };
+    }
+
+    /// Returns the algorithm computing the generations when following the
+    /// Game of Life's rules.
+    pub fn tick_engine(&self) -> TickEngine {
+        match self.lookup_table {
+            Some(_) => TickEngine::LookupTable,
+            None => TickEngine::Loop,
+        }
+    }
+
+    /// Returns the number of tiles of 32x32 cells which were computed during
+    /// the last tick.
+    pub fn computed_tiles(&self) -> u32 {
+        self.active_tiles.computed()
+    }
+
+    /// Returns the number of tiles of 32x32 cells which were skipped during
+    /// the last tick, because nothing changed around them.
+    pub fn skipped_tiles(&self) -> u32 {
+        self.active_tiles.skipped()
+    }
+
+    /// Re-initialize the pseudo-random number generator used by the stochastic
+    /// rules and the noise.
+    /// Two Universes with the same cells, settings and seed will always
+    /// evolve the same way.
+    pub fn set_seed(&mut self, seed: u32) {
+        self.finish_pending_tick();
+        self.rng = Rng::new(u64::from(seed));
+    }
+
+    /// Set the probability, from `0` to `1`, that a dead cell with exactly
+    /// three live neighbours becomes alive.
+    /// Defaults to `1`, as in the Game of Life.
+    pub fn set_birth_probability(&mut self, probability: f64) {
+        self.finish_pending_tick();
+        self.birth_probability = clamp_probability(probability);
+        self.active_tiles.invalidate();
+    }
+
+    /// Set the probability, from `0` to `1`, that a live cell with two or
+    /// three live neighbours stays alive.
+    /// Defaults to `1`, as in the Game of Life.
+    pub fn set_survival_probability(&mut self, probability: f64) {
+        self.finish_pending_tick();
+        self.survival_probability = clamp_probability(probability);
+        self.active_tiles.invalidate();
+    }
+
+    /// Set the probability, from `0` to `1`, for each cell to be flipped at
+    /// the end of each tick, whatever rule is followed.
+    /// Defaults to `0`, which means no noise.
+    ///
+    /// Note that a Margolus rule is not reversible anymore with noise.
+    pub fn set_noise_rate(&mut self, rate: f64) {
+        self.finish_pending_tick();
+        self.noise_rate = clamp_probability(rate);
+        self.active_tiles.invalidate();
     }
 
     /// Calculate the next iteration of our Universe based on its current state.
     pub fn tick(&mut self) {
+        self.finish_pending_tick();
+        if let Some(rule) = self.margolus_rule {
+            let odd_phase = self.generation % 2 == 1;
+            rule.step(&self.current_cells,
+                      &mut self.next_cells,
+                      self.height,
+                      self.width,
+                      odd_phase,
+                      false);
+            std::mem::swap(&mut self.current_cells, &mut self.next_cells);
+            self.apply_noise();
+            self.record_changes();
+        } else if self.is_deterministic() {
+            self.compute_deterministic_life_generation();
+        } else {
+            self.compute_life_generation();
+            self.apply_noise();
+            self.record_changes();
+        }
+        self.update_activity();
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Go back to the previous iteration of our Universe.
+    ///
+    /// This is only possible when following a reversible Margolus rule (such
+    /// as Critters, the Billiard Ball Machine or Tron). If it is not the case,
+    /// returns an error and do nothing.
+    pub fn reverse_tick(&mut self) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        let rule = match self.margolus_rule {
+            Some(rule) if rule.is_reversible() => rule,
+            _ => return Err(UniverseError::IrreversibleRule),
+        };
+        self.generation = self.generation.wrapping_sub(1);
+        let odd_phase = self.generation % 2 == 1;
+        rule.step(&self.current_cells,
+                  &mut self.next_cells,
+                  self.height,
+                  self.width,
+                  odd_phase,
+                  true);
+        std::mem::swap(&mut self.current_cells, &mut self.next_cells);
+        self.record_changes();
+        self.update_activity();
+        Ok(())
+    }
+
+    /// Set the given Margolus rule if the Universe's dimensions allow it.
+    fn set_margolus(&mut self, rule: MargolusRule) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        if !self.height.is_multiple_of(2) || !self.width.is_multiple_of(2) {
+            return Err(UniverseError::OddDimension {
+                height: self.height,
+                width: self.width,
+            });
+        }
+        self.margolus_rule = Some(rule);
+        self.active_tiles.invalidate();
+        Ok(())
+    }
+
+    /// Calculate the next iteration of our Universe according to the Game of
+    /// Life's rules.
+    fn compute_life_generation(&mut self) {
+        self.fill_ghost_border();
         for row in 0..self.height {
             for col in 0..self.width {
                 let idx = self.get_index(row, col);
                 let cell = self.current_cells[idx];
                 let live_neighbors = self.live_neighbor_count(row, col);
+                let next_cell = life_next_state(cell, live_neighbors);
 
-                let next_cell = match (cell, live_neighbors) {
-                    // Rule 1: Any live cell with fewer than two live neighbours
-                    // dies, as if caused by underpopulation.
-                    (Cell::Alive, x) if x < 2 => Cell::Dead,
-                    // Rule 2: Any live cell with two or three live neighbours
-                    // lives on to the next generation.
-                    (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-                    // Rule 3: Any live cell with more than three live
-                    // neighbours dies, as if by overpopulation.
-                    (Cell::Alive, x) if x > 3 => Cell::Dead,
-                    // Rule 4: Any dead cell with exactly three live neighbours
-                    // becomes a live cell, as if by reproduction.
-                    (Cell::Dead, 3) => Cell::Alive,
-                    // All other cells remain in the same state.
-                    (otherwise, _) => otherwise,
+                // Births and survivals only happen with their configured
+                // probability.
+                let next_cell = match (cell, next_cell) {
+                    (Cell::Dead, Cell::Alive)
+                        if !self.rng.chance(self.birth_probability) =>
+                            Cell::Dead,
+                    (Cell::Alive, Cell::Alive)
+                        if !self.rng.chance(self.survival_probability) =>
+                            Cell::Dead,
+                    (_, next_cell) => next_cell,
                 };
 
                 self.next_cells[idx] = next_cell;
@@ -105,14 +719,150 @@ impl Universe {
         std::mem::swap(&mut self.current_cells, &mut self.next_cells);
     }
 
-    // fn get_cell(&self, row: u32, column: u32) -> Option<Cell> {
-    //     if row >= self.height || column >= self.width {
-    //         None
-    //     } else {
-    //         let index = (row * self.width + column) as usize;
-    //         Some(self.current_cells[index])
-    //     }
-    // }
+    /// Returns `true` if the Game of Life's rules are followed without
+    /// stochastic transitions nor noise.
+    fn is_deterministic(&self) -> bool {
+        self.birth_probability >= 1. &&
+            self.survival_probability >= 1. &&
+            self.noise_rate <= 0.
+    }
+
+    /// Calculate the next iteration of our Universe according to the Game of
+    /// Life's rules, without stochastic transitions nor noise, and record the
+    /// cells which changed.
+    ///
+    /// Depending on the Universe's settings, this is done either on multiple
+    /// threads, through SIMD instructions, through a lookup table, by skipping
+    /// stable tiles or by computing every cell, the first enabled of those
+    /// being used.
+    fn compute_deterministic_life_generation(&mut self) {
+        #[cfg(feature = "parallel")]
+        {
+            if self.parallel {
+                self.compute_life_generation_in_parallel();
+                return;
+            }
+        }
+        #[cfg(feature = "simd")]
+        {
+            if self.simd && simd::simd_compiled() {
+                self.compute_life_generation_simd();
+                self.record_changes();
+                return;
+            }
+        }
+        if self.lookup_table.is_some() {
+            self.compute_life_generation_with_table();
+            self.record_changes();
+        } else if self.track_active_tiles {
+            self.compute_life_generation_in_active_tiles();
+        } else {
+            self.compute_life_generation();
+            self.record_changes();
+        }
+    }
+
+    /// Calculate the next iteration of our Universe according to the Game of
+    /// Life's rules, by only computing the tiles around those which changed
+    /// during the last generation.
+    ///
+    /// The other tiles are left as is in `next_cells`, which contains the
+    /// previous generation: as they did not change, it is also their current
+    /// state.
+    /// The changed cells are directly recorded while doing so.
+    fn compute_life_generation_in_active_tiles(&mut self) {
+        self.fill_ghost_border();
+        self.active_tiles.start_generation();
+        self.changed_cells.clear();
+        self.changed_states.clear();
+        let tile_columns = self.active_tiles.tile_columns();
+        for row in 0..self.height {
+            let first_tile = (row / TILE_SIZE) * tile_columns;
+            for tile_col in 0..tile_columns {
+                let tile = (first_tile + tile_col) as usize;
+                if !self.active_tiles.is_dirty(tile) {
+                    continue;
+                }
+                let first_col = tile_col * TILE_SIZE;
+                let last_col = (first_col + TILE_SIZE).min(self.width);
+                for col in first_col..last_col {
+                    let idx = self.get_index(row, col);
+                    let cell = self.current_cells[idx];
+                    let live_neighbors = self.live_neighbor_count(row, col);
+                    let next_cell = life_next_state(cell, live_neighbors);
+                    self.next_cells[idx] = next_cell;
+                    if next_cell != cell {
+                        self.active_tiles.set_changed(tile);
+                        self.changed_cells.push(row * self.width + col);
+                        self.changed_states.push(next_cell);
+                    }
+                }
+            }
+        }
+        self.active_tiles.end_generation();
+        std::mem::swap(&mut self.current_cells, &mut self.next_cells);
+    }
+
+    /// List the cells which changed during the last tick, by comparing the
+    /// current generation to the previous one, still in `next_cells`.
+    fn record_changes(&mut self) {
+        self.changed_cells.clear();
+        self.changed_states.clear();
+        for row in 0..self.height {
+            let start = self.get_index(row, 0);
+            let end = start + self.width as usize;
+            let generations = self.current_cells[start..end].iter()
+                .zip(self.next_cells[start..end].iter());
+            for (col, (&cell, &previous_cell)) in generations.enumerate() {
+                if cell != previous_cell {
+                    self.changed_cells.push(row * self.width + col as u32);
+                    self.changed_states.push(cell);
+                }
+            }
+        }
+        self.active_tiles.set_changes_from_cells(&self.changed_cells, self.width);
+    }
+
+    /// Update the ages of the cells and the heat map, if tracked, once a
+    /// generation has been computed and its changes recorded.
+    fn update_activity(&mut self) {
+        self.activity.update(&self.current_cells,
+                             self.height,
+                             self.width,
+                             &self.changed_cells);
+    }
+
+    /// Flip random cells, according to the noise rate.
+    fn apply_noise(&mut self) {
+        if self.noise_rate <= 0. {
+            return;
+        }
+        let len = (self.height * self.width) as usize;
+        let mut idx = 0;
+        loop {
+            // Rather than drawing a number for each cell, directly jump to
+            // the next flipped one by drawing the length of the gap from a
+            // geometric distribution.
+            if self.noise_rate < 1. {
+                let draw = 1. - self.rng.next_f64(); // in the (0, 1] range
+                let gap = draw.ln() / (1. - self.noise_rate).ln();
+                if gap >= (len - idx) as f64 {
+                    return;
+                }
+                idx += gap as usize;
+            }
+            if idx >= len {
+                return;
+            }
+            let width = self.width as usize;
+            let cell_idx = self.get_index((idx / width) as u32, (idx % width) as u32);
+            self.current_cells[cell_idx] = match self.current_cells[cell_idx] {
+                Cell::Dead => Cell::Alive,
+                Cell::Alive => Cell::Dead
+            };
+            idx += 1;
+        }
+    }
 
     /// Returns a mutable reference to a given Cell if found at the given row
     /// and column.
@@ -122,103 +872,134 @@ impl Universe {
         if row >= self.height || column >= self.width {
             None
         } else {
-            let index = (row * self.width + column) as usize;
+            let index = self.get_index(row, column);
             Some(&mut self.current_cells[index])
         }
     }
 
+    /// Replace the Universe's cells by a grid of the given dimensions, in
+    /// which the current cell at `(row, column)` is moved to
+    /// `(row + row_offset, column + column_offset)`.
+    /// Cells which end up outside the new grid are lost.
+    fn reframe(
+        &mut self,
+        new_height: u32,
+        new_width: u32,
+        row_offset: i64,
+        column_offset: i64
+    ) -> Result<(), UniverseError> {
+        let nb_cells = check_dimensions(new_height, new_width)?;
+        if self.margolus_rule.is_some() &&
+            (!new_height.is_multiple_of(2) || !new_width.is_multiple_of(2))
+        {
+            return Err(UniverseError::OddDimension {
+                height: new_height,
+                width: new_width,
+            });
+        }
+        let mut new_cells = vec![Cell::Dead; nb_cells];
+        for row in 0..self.height {
+            let new_row = i64::from(row) + row_offset;
+            if new_row < 0 || new_row >= i64::from(new_height) {
+                continue;
+            }
+            for col in 0..self.width {
+                let new_col = i64::from(col) + column_offset;
+                if new_col < 0 || new_col >= i64::from(new_width) {
+                    continue;
+                }
+                let new_idx = cell_index(new_width, new_row as u32, new_col as u32);
+                new_cells[new_idx] = self.current_cells[self.get_index(row, col)];
+            }
+        }
+        self.height = new_height;
+        self.width = new_width;
+        self.current_cells = new_cells;
+        self.next_cells = vec![Cell::Dead; nb_cells];
+        self.changed_cells.clear();
+        self.changed_states.clear();
+        self.activity.reset((new_height * new_width) as usize);
+        self.active_tiles = ActiveTiles::new(new_height, new_width);
+        Ok(())
+    }
+
+    /// Finish the tick started through `start_shared_tick`, if any, before
+    /// the Universe is updated, as workers may still read its cells.
+    fn finish_pending_tick(&mut self) {
+        #[cfg(feature = "wasm-threads")]
+        self.finish_shared_tick();
+    }
+
+    /// Returns an error if no Cell exists at the given row and column.
+    fn check_bounds(&self, row: u32, column: u32) -> Result<(), UniverseError> {
+        if row >= self.height || column >= self.width {
+            Err(UniverseError::OutOfBounds {
+                row,
+                column,
+                height: self.height,
+                width: self.width,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     /// Calculate index for a given row and column.
     ///
     /// This function perform no bound checking to verify that it is contained
     /// in the current boundaries of our Universe, use with care.
     fn get_index(&self, row: u32, column: u32) -> usize {
-        (row * self.width + column) as usize
+        cell_index(self.width, row, column)
     }
 
     /// Count every neighbours a given cell has, from `0` (no neighbour) to `8`
     /// (neighbours all around it).
+    ///
+    /// /!\ The ghost border should be up to date, see `fill_ghost_border`.
     fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
-
-        let cell_up = self.get_next_row_up_wrapping(row);
-        let cell_down = self.get_next_row_down_wrapping(row);
-        let cell_left = self.get_next_column_left_wrapping(column);
-        let cell_right = self.get_next_column_right_wrapping(column);
-
-        let nw = self.get_index(cell_up, cell_left);
-        count += self.current_cells[nw] as u8;
-
-        let n = self.get_index(cell_up, column);
-        count += self.current_cells[n] as u8;
-
-        let ne = self.get_index(cell_up, cell_right);
-        count += self.current_cells[ne] as u8;
-
-        let w = self.get_index(row, cell_left);
-        count += self.current_cells[w] as u8;
-
-        let e = self.get_index(row, cell_right);
-        count += self.current_cells[e] as u8;
-
-        let sw = self.get_index(cell_down, cell_left);
-        count += self.current_cells[sw] as u8;
-
-        let s = self.get_index(cell_down, column);
-        count += self.current_cells[s] as u8;
-
-        let se = self.get_index(cell_down, cell_right);
-        count += self.current_cells[se] as u8;
-
-        count
-    }
-
-    /// Get the next row on top of the given row, while wrapping to the last row
-    /// when reaching the top of the first row.
-    #[inline(always)]
-    fn get_next_row_up_wrapping(&self, row: u32) -> u32 {
-        if row == 0 {
-            self.height - 1
-        } else {
-            row - 1
-        }
-    }
-
-    /// Get the Nth row on the bottom of the given row, while wrapping to the
-    /// first row when reaching the bottom of the last row.
-    /// /!\ You should first ensure that the given `down` parameter is inferior
-    /// to `self.height` to avoid the returned value to overflow.
-    #[inline(always)]
-    fn get_next_row_down_wrapping(&self, row: u32) -> u32 {
-        if row == self.height - 1 {
-            0
-        } else {
-            row + 1
-        }
+        let idx = self.get_index(row, column);
+        let stride = self.stride() as usize;
+        let cells = &self.current_cells;
+        cells[idx - stride - 1] as u8 + cells[idx - stride] as u8 +
+            cells[idx - stride + 1] as u8 +
+            cells[idx - 1] as u8 + cells[idx + 1] as u8 +
+            cells[idx + stride - 1] as u8 + cells[idx + stride] as u8 +
+            cells[idx + stride + 1] as u8
     }
 
-    /// Get the Nth column on the left of the given column, while wrapping to
-    /// the last column when reaching the left of the first column.
-    /// /!\ You should first ensure that the given `left` parameter is inferior
-    /// to `self.width` to avoid the returned value to overflow.
-    #[inline(always)]
-    fn get_next_column_left_wrapping(&self, column: u32) -> u32 {
-        if column == 0 {
-            self.width - 1
-        } else {
-            column - 1
-        }
-    }
+    /// Fill the ghost border around the Universe's cells according to its
+    /// topology: with a copy of the opposite edges on a torus, so that a cell
+    /// on an edge has the cells on the other side as neighbours, or with dead
+    /// cells if the Universe is bounded.
+    fn fill_ghost_border(&mut self) {
+        let (height, width) = (self.height as usize, self.width as usize);
+        let stride = width + 2;
+        let cells = &mut self.current_cells;
+        match self.topology {
+            Topology::Torus => {
+                for row in 1..=height {
+                    let start = row * stride;
+                    cells[start] = cells[start + width];
+                    cells[start + width + 1] = cells[start + 1];
+                }
 
-    /// Get the Nth column on the right of the given column, while wrapping to
-    /// the first column when reaching the right of the last column.
-    /// /!\ You should first ensure that the given `right` parameter is inferior
-    /// to `self.width` to avoid the returned value to overflow.
-    #[inline(always)]
-    fn get_next_column_right_wrapping(&self, column: u32) -> u32 {
-        if column >= self.width - 1 {
-            0
-        } else {
-            column + 1
+                // Rows are copied with their ghost cells, which fills the
+                // corners.
+                cells.copy_within(height * stride..(height + 1) * stride, 0);
+                cells.copy_within(stride..2 * stride, (height + 1) * stride);
+            }
+            Topology::Bounded => {
+                for row in 1..=height {
+                    cells[row * stride] = Cell::Dead;
+                    cells[row * stride + width + 1] = Cell::Dead;
+                }
+                for cell in cells[..stride].iter_mut() {
+                    *cell = Cell::Dead;
+                }
+                for cell in cells[(height + 1) * stride..].iter_mut() {
+                    *cell = Cell::Dead;
+                }
+            }
         }
     }
 
@@ -276,7 +1057,26 @@ impl Universe {
 
     /// Construct a `glider` in our universe and place it centered at the given
     /// row and column.
+    ///
+    /// Panics if the row and column are out of the Universe's boundaries,
+    /// see `try_make_glider` for a version returning an error instead.
     pub fn make_glider(&mut self, row: u32, column: u32) {
+        if let Err(err) = self.try_make_glider(row, column) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Construct a `glider` in our universe and place it centered at the given
+    /// row and column.
+    /// Returns an error if the row and column are out of the Universe's
+    /// boundaries.
+    pub fn try_make_glider(
+        &mut self,
+        row: u32,
+        column: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.check_bounds(row, column)?;
         let cell_up = self.get_row_up_wrapping(row, 1);
         let cell_down = self.get_row_down_wrapping(row, 1);
         let cell_left = if column == 0 { self.width - 1 } else { column - 1 };
@@ -293,11 +1093,34 @@ impl Universe {
         for &cell_idx in glider_cells_indices.iter() {
             self.current_cells[cell_idx] = Cell::Alive;
         }
+        self.active_tiles.invalidate();
+        Ok(())
     }
 
     /// Construct a `pulsar` in our universe and place it centered at the given
     /// row and column.
+    /// Panics if the row and column are out of the Universe's boundaries or if
+    /// the pulsar does not fit in it, see `try_make_pulsar` for a version
+    /// returning an error instead.
     pub fn make_pulsar(&mut self, row: u32, column: u32) {
+        if let Err(err) = self.try_make_pulsar(row, column) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Construct a `pulsar` in our universe and place it centered at the given
+    /// row and column.
+    /// Returns an error if the row and column are out of the Universe's
+    /// boundaries, or if the Universe has less than 7 rows or columns, as the
+    /// pulsar's cells are up to 6 rows and columns away from its center.
+    pub fn try_make_pulsar(
+        &mut self,
+        row: u32,
+        column: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.check_bounds(row, column)?;
+        self.check_bounds(6, 6)?;
         let up_6 = self.get_row_up_wrapping(row, 6);
         let up_4 = self.get_row_up_wrapping(row, 4);
         let up_3 = self.get_row_up_wrapping(row, 3);
@@ -385,6 +1208,8 @@ impl Universe {
         for &cell_idx in pulsar_cells_indices.iter() {
             self.current_cells[cell_idx] = Cell::Alive;
         }
+        self.active_tiles.invalidate();
+        Ok(())
     }
 
     // ...
@@ -392,34 +1217,155 @@ impl Universe {
 
 // Specific methods used for tests
 impl Universe {
-    /// Get the dead and alive values of the entire universe.
-    pub fn get_cells(&self) -> &[Cell] {
-        &self.current_cells
+    /// Get the dead and alive values of the entire universe, in row then
+    /// column order.
+    pub fn get_cells(&self) -> Vec<Cell> {
+        (0..self.height)
+            .flat_map(|row| {
+                let start = self.get_index(row, 0);
+                self.current_cells[start..start + self.width as usize].iter().cloned()
+            })
+            .collect()
     }
 
     /// Set cells to be alive in a universe by passing the row and column
     /// of each cell as an array.
+    ///
+    /// Panics if one of the cells is out of the Universe's boundaries, see
+    /// `try_set_cells` for a version returning an error instead.
     pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
-        for (row, col) in cells.iter().cloned() {
+        if let Err(err) = self.try_set_cells(cells) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Set cells to be alive in a universe by passing the row and column
+    /// of each cell as an array.
+    ///
+    /// Returns an error without updating any cell if one of them is out of
+    /// the Universe's boundaries.
+    pub fn try_set_cells(
+        &mut self,
+        cells: &[(u32, u32)]
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        for &(row, col) in cells {
+            self.check_bounds(row, col)?;
+        }
+        for &(row, col) in cells {
             let idx = self.get_index(row, col);
-            // XXX TODO
             self.current_cells[idx] = Cell::Alive;
+            self.active_tiles.mark_cell(row, col);
         }
+        Ok(())
+    }
+
+    /// Returns the indices, as `row * width + column`, of the cells which
+    /// changed during the last tick.
+    pub fn changed_cells(&self) -> &[u32] {
+        &self.changed_cells
     }
 
+    /// Returns the new state of each cell of `changed_cells`.
+    pub fn changed_states(&self) -> &[Cell] {
+        &self.changed_states
+    }
 }
 
-impl fmt::Display for Universe {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let cells = &self.current_cells;
-        for line in cells.as_slice().chunks(self.width as usize) {
-            for &cell in line {
-                let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
-                write!(f, "{}", symbol)?;
+/// Check that a Universe can be created with the given dimensions and returns
+/// the number of cells to allocate, including its ghost border.
+fn check_dimensions(height: u32, width: u32) -> Result<usize, UniverseError> {
+    if height == 0 || width == 0 {
+        return Err(UniverseError::EmptyDimension { height, width });
+    }
+    let padded_height = height.checked_add(2);
+    let padded_width = width.checked_add(2);
+    match padded_height.zip(padded_width).and_then(|(h, w)| h.checked_mul(w)) {
+        Some(nb_cells) => Ok(nb_cells as usize),
+        None => Err(UniverseError::TooLarge { height, width }),
+    }
+}
+
+/// Returns the index of the cell at the given row and column in the cells of a
+/// Universe of the given width, stored with their ghost border.
+#[inline(always)]
+fn cell_index(width: u32, row: u32, column: u32) -> usize {
+    ((row + 1) * (width + 2) + column + 1) as usize
+}
+
+/// Returns the next state of a cell according to the Game of Life's rules,
+/// based on its current state and its number of live neighbours.
+#[inline(always)]
+fn life_next_state(cell: Cell, live_neighbors: u8) -> Cell {
+    match (cell, live_neighbors) {
+        // Rule 1: Any live cell with fewer than two live neighbours
+        // dies, as if caused by underpopulation.
+        (Cell::Alive, x) if x < 2 => Cell::Dead,
+        // Rule 2: Any live cell with two or three live neighbours
+        // lives on to the next generation.
+        (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
+        // Rule 3: Any live cell with more than three live
+        // neighbours dies, as if by overpopulation.
+        (Cell::Alive, x) if x > 3 => Cell::Dead,
+        // Rule 4: Any dead cell with exactly three live neighbours
+        // becomes a live cell, as if by reproduction.
+        (Cell::Dead, 3) => Cell::Alive,
+        // All other cells remain in the same state.
+        (otherwise, _) => otherwise,
+    }
+}
+
+/// Compute, according to the Game of Life's rules, the next generation of the
+/// band of rows beginning at `first_row` into `next_band`, whose length
+/// indicates the number of rows.
+/// `cells` is the current generation of a whole Universe of `height` rows and
+/// `width` columns, with an up to date ghost border, and `next_band` contains
+/// the same rows as stored in the next generation, ghost cells included.
+///
+/// Returns the indices and new states of the cells which changed.
+#[cfg(any(feature = "parallel", feature = "wasm-threads"))]
+fn compute_life_band(
+    cells: &[Cell],
+    width: u32,
+    first_row: u32,
+    next_band: &mut [Cell]
+) -> (Vec<u32>, Vec<Cell>) {
+    let mut changed_cells = Vec::new();
+    let mut changed_states = Vec::new();
+    let stride = width as usize + 2;
+    let nb_rows = (next_band.len() / stride) as u32;
+    for row in first_row..(first_row + nb_rows) {
+        let band_start = (row - first_row) as usize * stride;
+        for col in 0..width {
+            let idx = cell_index(width, row, col);
+            let live_neighbors = cells[idx - stride - 1] as u8 +
+                cells[idx - stride] as u8 + cells[idx - stride + 1] as u8 +
+                cells[idx - 1] as u8 + cells[idx + 1] as u8 +
+                cells[idx + stride - 1] as u8 + cells[idx + stride] as u8 +
+                cells[idx + stride + 1] as u8;
+            let next_cell = life_next_state(cells[idx], live_neighbors);
+            next_band[band_start + col as usize + 1] = next_cell;
+            if next_cell != cells[idx] {
+                changed_cells.push(row * width + col);
+                changed_states.push(next_cell);
             }
-            writeln!(f, "")?;
         }
+    }
+    (changed_cells, changed_states)
+}
 
-        Ok(())
+/// Bound the given probability to the `[0, 1]` range, `NaN` being considered
+/// as `0`.
+fn clamp_probability(probability: f64) -> f64 {
+    if probability.is_nan() {
+        0.
+    } else {
+        probability.clamp(0., 1.)
+    }
+}
+
+impl fmt::Display for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&TextRenderer::new().render(self))
     }
 }
@@ -0,0 +1,78 @@
+//! Computation of the Game of Life's generations through a precomputed table
+//! of the next state of a cell for each possible 3x3 neighbourhood.
+//!
+//! Each neighbourhood is represented as a 9-bit index, column by column: bits
+//! 0 to 2 are the cells on the left (from top to bottom), bits 3 to 5 the
+//! column of the cell itself and bits 6 to 8 those on the right.
+//! Moving to the next cell of a row then only means shifting the index by
+//! three bits and adding the column on the right, instead of counting the
+//! eight neighbours again.
+
+use crate::{Cell, Universe};
+
+/// Number of possible 3x3 neighbourhoods.
+const NB_NEIGHBORHOODS: usize = 1 << 9;
+
+/// Bit of the cell itself in a neighbourhood's index.
+const CENTER_BIT: usize = 1 << 4;
+
+/// Next state of a cell for each possible 3x3 neighbourhood.
+#[derive(Clone)]
+pub(crate) struct LookupTable {
+    next_states: [Cell; NB_NEIGHBORHOODS],
+}
+
+impl LookupTable {
+    /// Build the table from a rule giving the next state of a cell from its
+    /// current state and its number of live neighbours.
+    pub fn new(rule: fn(Cell, u8) -> Cell) -> LookupTable {
+        let mut next_states = [Cell::Dead; NB_NEIGHBORHOODS];
+        for (neighborhood, next_state) in next_states.iter_mut().enumerate() {
+            let cell = if neighborhood & CENTER_BIT == 0 {
+                Cell::Dead
+            } else {
+                Cell::Alive
+            };
+            let live_neighbors = (neighborhood & !CENTER_BIT).count_ones() as u8;
+            *next_state = rule(cell, live_neighbors);
+        }
+        LookupTable { next_states }
+    }
+
+    /// Compute the next generation of a row of cells into `next_row`, based
+    /// on its current state and the rows directly up and down.
+    /// Those rows include the ghost cells on the left and right of the row,
+    /// which `next_row` does not.
+    fn compute_row(&self, up: &[Cell], row: &[Cell], down: &[Cell], next_row: &mut [Cell]) {
+        let column = |col: usize| {
+            up[col] as usize | (row[col] as usize) << 1 | (down[col] as usize) << 2
+        };
+        let mut neighborhood = column(0) << 3 | column(1) << 6;
+        for (col, next_cell) in next_row.iter_mut().enumerate() {
+            neighborhood = neighborhood >> 3 | column(col + 2) << 6;
+            *next_cell = self.next_states[neighborhood];
+        }
+    }
+}
+
+impl Universe {
+    /// Calculate the next iteration of our Universe according to the Game of
+    /// Life's rules, through its lookup table.
+    ///
+    /// /!\ The lookup table engine should be selected.
+    pub(crate) fn compute_life_generation_with_table(&mut self) {
+        self.fill_ghost_border();
+        let table = self.lookup_table.as_ref()
+            .expect("the lookup table engine is not selected");
+        let width = self.width as usize;
+        let stride = width + 2;
+        let padded_rows = self.current_cells.windows(3 * stride).step_by(stride);
+        let next_rows = self.next_cells[stride..].chunks_mut(stride);
+        for (rows, next_row) in padded_rows.zip(next_rows) {
+            let (up, rows) = rows.split_at(stride);
+            let (row, down) = rows.split_at(stride);
+            table.compute_row(up, row, down, &mut next_row[1..=width]);
+        }
+        std::mem::swap(&mut self.current_cells, &mut self.next_cells);
+    }
+}
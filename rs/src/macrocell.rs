@@ -0,0 +1,464 @@
+//! Quadtrees of cells, for patterns too large to be stored cell by cell, and
+//! their reading and writing in the Macrocell format of Golly.
+//!
+//! A Macrocell file begins with a `[M2]` header line, followed by comment
+//! lines beginning with `#`, of which `#R <rule>` gives the rule of the
+//! pattern. Each of the next lines describes a node of the quadtree, the last
+//! one being the root:
+//!
+//!   - a block of 8x8 cells, written as rows of `.` for dead cells and `*` for
+//!     live ones, each row ending with `$`, dead cells at the end of the rows
+//!     and empty rows at the end of the block being omitted:
+//!
+//!     ```text
+//!     .*$..*$***$
+//!     ```
+//!
+//!   - a node of `2^level` cells of side, written as its level followed by its
+//!     north-west, north-east, south-west and south-east quarters, each one
+//!     being the number of a previous line, from `1`, or `0` for a quarter
+//!     without live cells:
+//!
+//!     ```text
+//!     4 1 0 0 0
+//!     ```
+//!
+//! Identical quarters are only written once, so that patterns repeating the
+//! same parts stay small however large they are.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+use crate::{Cell, MacrocellError, Pattern, Universe, UniverseError};
+
+/// Level of the blocks of 8x8 cells.
+const LEAF_LEVEL: u32 = 3;
+
+/// Highest level of a node, so that the coordinates of its cells fit in 64
+/// bits.
+const MAX_LEVEL: u32 = 63;
+
+/// Index of the node standing for quarters without live cells, at any level.
+const EMPTY: u32 = 0;
+
+/// Node of a quadtree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Node {
+    /// Block of 8x8 cells, as one bit per cell, set for live cells, from the
+    /// least significant bit, in row then column order.
+    Leaf(u64),
+
+    /// Square of `2^level` cells of side, made of the nodes of its
+    /// north-west, north-east, south-west and south-east quarters.
+    Branch { level: u32, children: [u32; 4] },
+}
+
+/// Square of cells stored as a quadtree, where identical parts are shared,
+/// such as loaded from a Macrocell file.
+///
+/// The cell at row `0` and column `0` is the top-left corner of the root of
+/// the tree.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Quadtree {
+    /// Nodes of the tree, each one after its children. The first one stands
+    /// for every node without live cells.
+    nodes: Vec<Node>,
+
+    /// Index of each node in `nodes`, so that identical nodes are shared.
+    indices: HashMap<Node, u32>,
+
+    /// Index of the root of the tree in `nodes`.
+    root: u32,
+
+    /// Level of the root, the tree having `2^level` cells of side.
+    level: u32,
+
+    /// Rule the pattern was designed for, as written in its file, if known.
+    rule: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Quadtree {
+    /// Read a quadtree in the Macrocell format.
+    pub fn from_macrocell(text: &str) -> Result<Quadtree, MacrocellError> {
+        let mut lines = (1..).zip(text.lines());
+        match lines.next() {
+            Some((_, header)) if header.starts_with("[M2]") => {}
+            _ => return Err(MacrocellError::MissingHeader),
+        }
+
+        let mut tree = Quadtree::empty(None);
+        // Index in `tree.nodes` and level of each line describing a node, the
+        // first one standing for the empty quarters.
+        let mut file_nodes = vec![(EMPTY, 0)];
+        for (line_nb, line) in lines {
+            let line = line.trim();
+            if let Some(rule) = line.strip_prefix("#R") {
+                tree.rule = Some(rule.trim().to_owned());
+                continue;
+            } else if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid_line = MacrocellError::InvalidLine { line: line_nb };
+            let invalid_node = MacrocellError::InvalidNode { line: line_nb };
+
+            if !line.starts_with(|character: char| character.is_ascii_digit()) {
+                let bits = parse_leaf(line).ok_or(invalid_line)?;
+                file_nodes.push((tree.intern(Node::Leaf(bits)), LEAF_LEVEL));
+                continue;
+            }
+            let numbers = line.split_whitespace()
+                .map(|field| field.parse::<u32>().map_err(|_| invalid_line))
+                .collect::<Result<Vec<u32>, MacrocellError>>()?;
+            if numbers.len() != 5 {
+                return Err(invalid_line);
+            }
+            let level = numbers[0];
+            if level > MAX_LEVEL {
+                return Err(MacrocellError::TooLarge);
+            } else if level <= LEAF_LEVEL {
+                return Err(invalid_node);
+            }
+            let mut children = [EMPTY; 4];
+            for (child, &number) in children.iter_mut().zip(&numbers[1..]) {
+                let &(idx, child_level) = file_nodes.get(number as usize).ok_or(invalid_node)?;
+                if number != 0 && child_level != level - 1 {
+                    return Err(invalid_node);
+                }
+                *child = idx;
+            }
+            file_nodes.push((tree.intern(Node::Branch { level, children }), level));
+        }
+
+        if file_nodes.len() > 1 {
+            let (root, level) = file_nodes[file_nodes.len() - 1];
+            tree.root = root;
+            tree.level = level;
+        }
+        Ok(tree)
+    }
+
+    /// Write the quadtree in the Macrocell format.
+    ///
+    /// A tree without live cells is written as a single empty block.
+    pub fn to_macrocell(&self) -> String {
+        let mut text = String::from("[M2] (wasm-game-of-life)\n");
+        if let Some(rule) = &self.rule {
+            writeln!(text, "#R {}", rule).unwrap();
+        }
+        if self.root == EMPTY {
+            text.push_str("$\n");
+        } else {
+            let mut numbers = vec![0; self.nodes.len()];
+            let mut nb_written = 0;
+            self.write_node(self.root, &mut numbers, &mut nb_written, &mut text);
+        }
+        text
+    }
+
+    /// Create a quadtree with the cells of the given pattern, its top-left
+    /// corner being at row `0` and column `0`, and with its rule.
+    ///
+    /// A Universe can be stored as a quadtree through `Universe::to_pattern`.
+    pub fn from_pattern(pattern: &Pattern) -> Quadtree {
+        let side = u64::from(pattern.height().max(pattern.width())).max(1 << LEAF_LEVEL);
+        let level = side.next_power_of_two().trailing_zeros();
+        let mut tree = Quadtree::empty(pattern.rule());
+        tree.root = tree.build(pattern, level, 0, 0);
+        tree.level = level;
+        tree
+    }
+
+    /// Returns a pattern of the region of `height` rows and `width` columns
+    /// beginning at the given row and column, with the rule of the tree.
+    ///
+    /// The parts of that region outside of the tree are dead cells.
+    pub fn to_pattern(
+        &self,
+        row: u64,
+        column: u64,
+        height: u32,
+        width: u32
+    ) -> Result<Pattern, MacrocellError> {
+        let mut pattern = Pattern::new(height, width).map_err(|_| MacrocellError::TooLarge)?;
+        pattern.set_rule(self.rule.clone());
+        self.fill(&mut pattern, self.root, self.level, (0, 0), (row, column));
+        Ok(pattern)
+    }
+
+    /// Returns the level of the root of the tree, which has `2^level` cells
+    /// of side.
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// Returns the state of the cell at the given row and column, which is
+    /// dead outside of the tree.
+    pub fn get(&self, row: u64, column: u64) -> Cell {
+        if (row | column) >> self.level != 0 {
+            return Cell::Dead;
+        }
+        let mut node = self.root;
+        let mut level = self.level;
+        loop {
+            match self.nodes[node as usize] {
+                Node::Leaf(bits) => {
+                    let bit = (row % 8) * 8 + column % 8;
+                    return if bits >> bit & 1 == 1 { Cell::Alive } else { Cell::Dead };
+                }
+                Node::Branch { children, .. } => {
+                    level -= 1;
+                    let quarter = (row >> level & 1) * 2 + (column >> level & 1);
+                    node = children[quarter as usize];
+                }
+            }
+        }
+    }
+
+    /// Returns the number of live cells of the tree, or `u64::MAX` if it is
+    /// larger.
+    pub fn population(&self) -> u64 {
+        let mut populations: Vec<u64> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let population = match *node {
+                Node::Leaf(bits) => u64::from(bits.count_ones()),
+                Node::Branch { children, .. } => children.iter()
+                    .fold(0u64, |sum, &child| sum.saturating_add(populations[child as usize])),
+            };
+            populations.push(population);
+        }
+        populations[self.root as usize]
+    }
+
+    /// Returns the rule the pattern was designed for, if known.
+    pub fn rule(&self) -> Option<String> {
+        self.rule.clone()
+    }
+
+    /// Set the rule the pattern was designed for, written in Macrocell files.
+    pub fn set_rule(&mut self, rule: Option<String>) {
+        self.rule = rule;
+    }
+}
+
+impl Quadtree {
+    /// Returns the first row, first column, height and width of the smallest
+    /// rectangle containing every live cell, or `None` if there is none.
+    pub fn bounding_box(&self) -> Option<(u64, u64, u64, u64)> {
+        // Top, left, bottom and right cells of each node, from its corner.
+        let mut bounds: Vec<Option<(u64, u64, u64, u64)>> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let node_bounds = match *node {
+                Node::Leaf(bits) => (0..64)
+                    .filter(|bit| bits >> bit & 1 == 1)
+                    .map(|bit| (bit / 8, bit % 8))
+                    .fold(None, |bounds, (row, col)| Some(merge_bounds(bounds, (row, col, row, col)))),
+                Node::Branch { level, children } => {
+                    let half = 1 << (level - 1);
+                    let offsets = [(0, 0), (0, half), (half, 0), (half, half)];
+                    children.iter()
+                        .zip(&offsets)
+                        .filter_map(|(&child, &(row, col))| {
+                            bounds[child as usize].map(|(top, left, bottom, right)| {
+                                (top + row, left + col, bottom + row, right + col)
+                            })
+                        })
+                        .fold(None, |bounds, child_bounds| Some(merge_bounds(bounds, child_bounds)))
+                }
+            };
+            bounds.push(node_bounds);
+        }
+        bounds[self.root as usize]
+            .map(|(top, left, bottom, right)| (top, left, bottom - top + 1, right - left + 1))
+    }
+
+    /// Create a quadtree without live cells.
+    fn empty(rule: Option<String>) -> Quadtree {
+        Quadtree {
+            nodes: vec![Node::Leaf(0)],
+            indices: HashMap::new(),
+            root: EMPTY,
+            level: LEAF_LEVEL,
+            rule,
+        }
+    }
+
+    /// Returns the index of the given node, adding it to the tree if it is
+    /// not already there.
+    fn intern(&mut self, node: Node) -> u32 {
+        match node {
+            Node::Leaf(0) => return EMPTY,
+            Node::Branch { children, .. } if children == [EMPTY; 4] => return EMPTY,
+            _ => {}
+        }
+        let nodes = &mut self.nodes;
+        *self.indices.entry(node).or_insert_with(|| {
+            nodes.push(node);
+            nodes.len() as u32 - 1
+        })
+    }
+
+    /// Add the nodes of the square of the pattern of `2^level` cells of side
+    /// beginning at the given row and column, returning the index of the
+    /// square.
+    fn build(&mut self, pattern: &Pattern, level: u32, row: u64, column: u64) -> u32 {
+        let (height, width) = (u64::from(pattern.height()), u64::from(pattern.width()));
+        if row >= height || column >= width {
+            return EMPTY;
+        }
+        if level == LEAF_LEVEL {
+            let mut bits = 0;
+            for bit in 0..64 {
+                let (cell_row, cell_col) = (row + bit / 8, column + bit % 8);
+                if cell_row < height && cell_col < width &&
+                    pattern.get(cell_row as u32, cell_col as u32) == Cell::Alive {
+                    bits |= 1 << bit;
+                }
+            }
+            return self.intern(Node::Leaf(bits));
+        }
+        let half = 1 << (level - 1);
+        let children = [
+            self.build(pattern, level - 1, row, column),
+            self.build(pattern, level - 1, row, column + half),
+            self.build(pattern, level - 1, row + half, column),
+            self.build(pattern, level - 1, row + half, column + half),
+        ];
+        self.intern(Node::Branch { level, children })
+    }
+
+    /// Set the live cells of the node of the given level and top-left corner
+    /// which are in the pattern, whose top-left corner is at `origin`.
+    fn fill(
+        &self,
+        pattern: &mut Pattern,
+        node: u32,
+        level: u32,
+        corner: (u64, u64),
+        origin: (u64, u64)
+    ) {
+        let end = (origin.0.saturating_add(u64::from(pattern.height())),
+                   origin.1.saturating_add(u64::from(pattern.width())));
+        let side = 1 << level;
+        if node == EMPTY || corner.0 >= end.0 || corner.1 >= end.1 ||
+            corner.0 + side <= origin.0 || corner.1 + side <= origin.1 {
+            return;
+        }
+        match self.nodes[node as usize] {
+            Node::Leaf(bits) => {
+                for bit in (0..64).filter(|bit| bits >> bit & 1 == 1) {
+                    let (row, col) = (corner.0 + bit / 8, corner.1 + bit % 8);
+                    if (origin.0..end.0).contains(&row) && (origin.1..end.1).contains(&col) {
+                        pattern.set((row - origin.0) as u32, (col - origin.1) as u32, Cell::Alive);
+                    }
+                }
+            }
+            Node::Branch { children, .. } => {
+                let half = side / 2;
+                let offsets = [(0, 0), (0, half), (half, 0), (half, half)];
+                for (&child, &(row, col)) in children.iter().zip(&offsets) {
+                    self.fill(pattern, child, level - 1, (corner.0 + row, corner.1 + col), origin);
+                }
+            }
+        }
+    }
+
+    /// Write the given node after its children, unless already written,
+    /// and returns its number in the file.
+    fn write_node(
+        &self,
+        node: u32,
+        numbers: &mut [u32],
+        nb_written: &mut u32,
+        text: &mut String
+    ) -> u32 {
+        if node == EMPTY || numbers[node as usize] != 0 {
+            return numbers[node as usize];
+        }
+        match self.nodes[node as usize] {
+            Node::Leaf(bits) => {
+                let mut pending_rows = 0;
+                for row in 0..8 {
+                    let row_bits = (bits >> (row * 8)) as u8;
+                    if row_bits == 0 {
+                        pending_rows += 1;
+                        continue;
+                    }
+                    text.extend((0..pending_rows).map(|_| '$'));
+                    pending_rows = 1;
+                    let len = 8 - row_bits.leading_zeros();
+                    text.extend((0..len).map(|col| if row_bits >> col & 1 == 1 { '*' } else { '.' }));
+                }
+                text.push_str("$\n");
+            }
+            Node::Branch { level, children } => {
+                let mut child_numbers = [0; 4];
+                for (number, &child) in child_numbers.iter_mut().zip(&children) {
+                    *number = self.write_node(child, numbers, nb_written, text);
+                }
+                let [north_west, north_east, south_west, south_east] = child_numbers;
+                writeln!(text, "{} {} {} {} {}",
+                         level, north_west, north_east, south_west, south_east).unwrap();
+            }
+        }
+        *nb_written += 1;
+        numbers[node as usize] = *nb_written;
+        *nb_written
+    }
+}
+
+/// Parse a block of 8x8 cells of a Macrocell file.
+///
+/// Returns `None` if it contains other characters than `.`, `*` and `$`, or
+/// live cells out of the block.
+fn parse_leaf(line: &str) -> Option<u64> {
+    let (mut row, mut col) = (0, 0);
+    let mut bits = 0;
+    for character in line.chars() {
+        match character {
+            '.' => col += 1,
+            '*' if row < 8 && col < 8 => {
+                bits |= 1 << (row * 8 + col);
+                col += 1;
+            }
+            '$' => {
+                row += 1;
+                col = 0;
+            }
+            _ => return None,
+        }
+    }
+    Some(bits)
+}
+
+/// Returns the smallest rectangle containing both rectangles given as their
+/// top, left, bottom and right cells.
+fn merge_bounds(
+    bounds: Option<(u64, u64, u64, u64)>,
+    other: (u64, u64, u64, u64)
+) -> (u64, u64, u64, u64) {
+    match bounds {
+        None => other,
+        Some((top, left, bottom, right)) =>
+            (top.min(other.0), left.min(other.1), bottom.max(other.2), right.max(other.3)),
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Create a Universe of the smallest rectangle containing every live cell
+    /// of the quadtree, with the same cells.
+    ///
+    /// Returns an error if there is no live cell or if the rectangle is too
+    /// large for a Universe. `Quadtree::to_pattern` flattens any other region.
+    pub fn from_quadtree(quadtree: &Quadtree) -> Result<Universe, MacrocellError> {
+        let (row, column, height, width) = quadtree.bounding_box()
+            .ok_or(UniverseError::NoLiveCell)?;
+        let height = u32::try_from(height).map_err(|_| MacrocellError::TooLarge)?;
+        let width = u32::try_from(width).map_err(|_| MacrocellError::TooLarge)?;
+        let pattern = quadtree.to_pattern(row, column, height, width)?;
+        Ok(Universe::from_pattern(&pattern)?)
+    }
+}
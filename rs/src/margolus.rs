@@ -0,0 +1,165 @@
+use crate::{cell_index, Cell};
+
+/// Rule of a Margolus block cellular automaton.
+///
+/// In a Margolus neighborhood, the grid is partitioned into 2x2 blocks whose
+/// origin alternates, from one generation to the next, between even and odd
+/// rows and columns.
+/// Each block is then replaced as a whole, according to a 16-entry table.
+///
+/// A block is encoded as a 4-bit number, where each bit is set if the
+/// corresponding cell is alive:
+///   - bit `0` (value `1`) is the top-left cell
+///   - bit `1` (value `2`) is the top-right cell
+///   - bit `2` (value `4`) is the bottom-left cell
+///   - bit `3` (value `8`) is the bottom-right cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MargolusRule {
+    table: [u8; 16],
+
+    /// Inverse of `table`, only set if `table` is a permutation of every
+    /// possible blocks.
+    inverse: Option<[u8; 16]>,
+}
+
+impl MargolusRule {
+    /// Create a new `MargolusRule` from a 16-entry block table, where the
+    /// value at index `i` is what the block `i` becomes in the next
+    /// generation.
+    ///
+    /// Returns `None` if the table does not contain exactly 16 entries or if
+    /// one of them is not a valid block (superior to `15`).
+    pub fn new(table: &[u8]) -> Option<MargolusRule> {
+        if table.len() != 16 || table.iter().any(|&block| block > 15) {
+            return None;
+        }
+        let mut rule_table = [0; 16];
+        rule_table.copy_from_slice(table);
+
+        let mut inverse = [0; 16];
+        let mut seen = [false; 16];
+        for (block, &next_block) in rule_table.iter().enumerate() {
+            seen[next_block as usize] = true;
+            inverse[next_block as usize] = block as u8;
+        }
+        let inverse = if seen.iter().all(|&s| s) { Some(inverse) } else { None };
+        Some(MargolusRule { table: rule_table, inverse })
+    }
+
+    /// The reversible "Critters" rule: every block not having exactly two
+    /// live cells is complemented, and blocks which had exactly three live
+    /// cells are also rotated by 180 degrees.
+    pub fn critters() -> MargolusRule {
+        let mut table = [0; 16];
+        for (block, next_block) in table.iter_mut().enumerate() {
+            let block = block as u8;
+            *next_block = match block.count_ones() {
+                2 => block,
+                3 => rotate_block_180(!block & 0xF),
+                _ => !block & 0xF,
+            };
+        }
+        MargolusRule::new(&table).unwrap()
+    }
+
+    /// The "Billiard Ball Machine" rule: a single live cell moves to the
+    /// opposite corner of its block and two diagonally-opposed cells bounce
+    /// on the other diagonal. Every other block stays the same.
+    pub fn billiard_ball_machine() -> MargolusRule {
+        let mut table = [0; 16];
+        for (block, next_block) in table.iter_mut().enumerate() {
+            let block = block as u8;
+            *next_block = match block {
+                0b0001 | 0b0010 | 0b0100 | 0b1000 => rotate_block_180(block),
+                0b1001 => 0b0110,
+                0b0110 => 0b1001,
+                _ => block,
+            };
+        }
+        MargolusRule::new(&table).unwrap()
+    }
+
+    /// The "Tron" rule: blocks whose cells are all in the same state are
+    /// complemented. Every other block stays the same.
+    pub fn tron() -> MargolusRule {
+        let mut table = [0; 16];
+        for (block, next_block) in table.iter_mut().enumerate() {
+            *next_block = match block {
+                0b0000 => 0b1111,
+                0b1111 => 0b0000,
+                _ => block as u8,
+            };
+        }
+        MargolusRule::new(&table).unwrap()
+    }
+
+    /// Returns the 16-entry block table of this rule.
+    pub fn table(&self) -> &[u8; 16] {
+        &self.table
+    }
+
+    /// Returns `true` if this rule is a permutation of blocks, meaning that
+    /// every generation can be exactly reverted.
+    pub fn is_reversible(&self) -> bool {
+        self.inverse.is_some()
+    }
+
+    /// Compute the next generation of `cells` into `next_cells`, by
+    /// replacing every 2x2 blocks on a toroidal grid. Both are stored with a
+    /// ghost border, which is left untouched.
+    ///
+    /// `odd_phase` indicates whether blocks begin at odd rows and columns
+    /// (blocks then wrap around the edges) instead of even ones.
+    /// If `reverse` is set, the inverse table is used instead, allowing to
+    /// go back to the previous generation. In that case, the rule should be
+    /// reversible.
+    ///
+    /// /!\ Both `height` and `width` need to be even.
+    pub(crate) fn step(
+        &self,
+        cells: &[Cell],
+        next_cells: &mut [Cell],
+        height: u32,
+        width: u32,
+        odd_phase: bool,
+        reverse: bool
+    ) {
+        let table = if reverse {
+            self.inverse.as_ref().expect("a reversible rule")
+        } else {
+            &self.table
+        };
+        let offset = if odd_phase { 1 } else { 0 };
+        for top in (offset..height).step_by(2) {
+            let bottom = if top + 1 == height { 0 } else { top + 1 };
+            for left in (offset..width).step_by(2) {
+                let right = if left + 1 == width { 0 } else { left + 1 };
+                let indices = [
+                    cell_index(width, top, left),
+                    cell_index(width, top, right),
+                    cell_index(width, bottom, left),
+                    cell_index(width, bottom, right),
+                ];
+                let block = indices.iter()
+                    .enumerate()
+                    .fold(0, |acc, (bit, &idx)| acc | ((cells[idx] as u8) << bit));
+                let next_block = table[block as usize];
+                for (bit, &idx) in indices.iter().enumerate() {
+                    next_cells[idx] = if next_block & (1 << bit) != 0 {
+                        Cell::Alive
+                    } else {
+                        Cell::Dead
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Rotate a 4-bit block by 180 degrees, swapping its opposite corners.
+fn rotate_block_180(block: u8) -> u8 {
+    ((block & 0b0001) << 3) |
+    ((block & 0b0010) << 1) |
+    ((block & 0b0100) >> 1) |
+    ((block & 0b1000) >> 3)
+}
@@ -0,0 +1,53 @@
+//! Computation of the Game of Life's generations on multiple threads.
+
+use rayon::prelude::*;
+use crate::{compute_life_band, Cell, Universe};
+
+/// Number of bands of rows each thread should have to compute, so that a
+/// thread finishing early can take work from the others.
+const BANDS_PER_THREAD: usize = 4;
+
+impl Universe {
+    /// Enable or disable the computation of each tick on multiple threads.
+    /// Disabled by default.
+    ///
+    /// This has no effect on the result, only on performance. Note that
+    /// ticks are only computed in parallel when following the Game of Life's
+    /// rules, without stochastic transitions nor noise. In that case, the
+    /// tracking of stable parts of the Universe is not used.
+    pub fn set_parallel(&mut self, enabled: bool) {
+        self.finish_pending_tick();
+        self.parallel = enabled;
+    }
+
+    /// Calculate the next iteration of our Universe according to the Game of
+    /// Life's rules, by splitting it into bands of rows computed in parallel.
+    pub(crate) fn compute_life_generation_in_parallel(&mut self) {
+        let nb_bands = rayon::current_num_threads() * BANDS_PER_THREAD;
+        let band_height = (self.height as usize).div_ceil(nb_bands).max(1);
+
+        self.fill_ghost_border();
+        let (height, width) = (self.height as usize, self.width);
+        let stride = self.stride() as usize;
+        let cells = &self.current_cells;
+        let band_changes: Vec<(Vec<u32>, Vec<Cell>)> = self.next_cells
+            [stride..(height + 1) * stride]
+            .par_chunks_mut(band_height * stride)
+            .enumerate()
+            .map(|(band, next_band)| {
+                let first_row = (band * band_height) as u32;
+                compute_life_band(cells, width, first_row, next_band)
+            })
+            .collect();
+        std::mem::swap(&mut self.current_cells, &mut self.next_cells);
+
+        // Changes of each band are already in the right order.
+        self.changed_cells.clear();
+        self.changed_states.clear();
+        for (changed_cells, changed_states) in band_changes {
+            self.changed_cells.extend(changed_cells);
+            self.changed_states.extend(changed_states);
+        }
+        self.active_tiles.set_changes_from_cells(&self.changed_cells, self.width);
+    }
+}
@@ -0,0 +1,389 @@
+//! Patterns of cells independent from any Universe, and their reading and
+//! writing in the usual text formats of Game of Life programs:
+//!
+//!   - the Run Length Encoded (RLE) format, where runs of identical cells are
+//!     written as a count followed by `b` for dead cells or `o` for live ones,
+//!     rows being separated by `$` and the pattern ending with `!`:
+//!
+//!     ```text
+//!     #C A glider
+//!     x = 3, y = 3, rule = B3/S23
+//!     bo$2bo$3o!
+//!     ```
+//!
+//!   - the plaintext format, where each line is a row of cells written as `.`
+//!     when dead and `O` when alive, lines beginning with `!` being comments:
+//!
+//!     ```text
+//!     !Name: Glider
+//!     .O.
+//!     ..O
+//!     OOO
+//!     ```
+
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+use crate::{Cell, PasteMode, PatternError, Universe, UniverseError};
+
+/// Maximum length of the lines of the cells of a RLE file, as recommended by
+/// the format.
+const RLE_LINE_LENGTH: usize = 70;
+
+/// Rectangle of cells, such as loaded from a pattern file.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    height: u32,
+    width: u32,
+
+    /// Cells of the pattern, in row then column order.
+    cells: Vec<Cell>,
+
+    /// Rule the pattern was designed for, as written in its file (e.g.
+    /// `B3/S23` for the Game of Life), if known.
+    rule: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Pattern {
+    /// Create a pattern of the given dimensions whose cells are all dead.
+    pub fn new(height: u32, width: u32) -> Result<Pattern, PatternError> {
+        let nb_cells = height.checked_mul(width).ok_or(PatternError::TooLarge)?;
+        Ok(Pattern {
+            height,
+            width,
+            cells: vec![Cell::Dead; nb_cells as usize],
+            rule: None,
+        })
+    }
+
+    /// Read a pattern in the RLE format.
+    pub fn from_rle(text: &str) -> Result<Pattern, PatternError> {
+        let mut lines = (1..).zip(text.lines())
+            .map(|(nb, line)| (nb, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let (header_nb, header) = lines.next().ok_or(PatternError::MissingHeader)?;
+        if !header.starts_with('x') {
+            return Err(PatternError::MissingHeader);
+        }
+        let mut pattern = parse_rle_header(header)
+            .ok_or(PatternError::InvalidHeader { line: header_nb })??;
+
+        let (mut row, mut col) = (0, 0);
+        let mut count: Option<u32> = None;
+        'lines: for (line_nb, line) in lines {
+            for character in line.chars() {
+                if let Some(digit) = character.to_digit(10) {
+                    let run = count.unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|run| run.checked_add(digit))
+                        .ok_or(PatternError::OutOfBounds { line: line_nb })?;
+                    count = Some(run);
+                    continue;
+                }
+                let run = count.take().unwrap_or(1);
+                match character {
+                    'b' | 'o' => {
+                        let end = col + u64::from(run);
+                        if end > u64::from(pattern.width) ||
+                            (run > 0 && row >= pattern.height) {
+                            return Err(PatternError::OutOfBounds { line: line_nb });
+                        }
+                        if character == 'o' {
+                            let first = pattern.index(row, col as u32);
+                            for cell in &mut pattern.cells[first..first + run as usize] {
+                                *cell = Cell::Alive;
+                            }
+                        }
+                        col = end;
+                    }
+                    '$' => {
+                        row = row.saturating_add(run);
+                        col = 0;
+                    }
+                    '!' => break 'lines,
+                    character if character.is_whitespace() => {}
+                    character => {
+                        return Err(PatternError::UnexpectedCharacter {
+                            line: line_nb,
+                            character,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// Read a pattern in the plaintext format.
+    ///
+    /// Rows shorter than the longest one are completed with dead cells.
+    pub fn from_plaintext(text: &str) -> Result<Pattern, PatternError> {
+        let mut rows = Vec::new();
+        for (line_nb, line) in (1..).zip(text.lines()) {
+            if line.starts_with('!') {
+                continue;
+            }
+            let row = line.trim_end().chars()
+                .map(|character| match character {
+                    '.' => Ok(Cell::Dead),
+                    'O' | '*' => Ok(Cell::Alive),
+                    character => Err(PatternError::UnexpectedCharacter {
+                        line: line_nb,
+                        character,
+                    }),
+                })
+                .collect::<Result<Vec<Cell>, PatternError>>()?;
+            rows.push(row);
+        }
+        // Blank lines at the end of the file are not rows.
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+
+        let height = u32::try_from(rows.len()).map_err(|_| PatternError::TooLarge)?;
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let width = u32::try_from(width).map_err(|_| PatternError::TooLarge)?;
+        let mut pattern = Pattern::new(height, width)?;
+        for (row_cells, row) in pattern.cells.chunks_exact_mut(width.max(1) as usize).zip(rows) {
+            row_cells[..row.len()].copy_from_slice(&row);
+        }
+        Ok(pattern)
+    }
+
+    /// Read a pattern either in the RLE or in the plaintext format, depending
+    /// on the header of the text.
+    pub fn parse(text: &str) -> Result<Pattern, PatternError> {
+        let is_rle = text.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .is_some_and(|line| line.starts_with('x'));
+        if is_rle {
+            Pattern::from_rle(text)
+        } else {
+            Pattern::from_plaintext(text)
+        }
+    }
+
+    /// Write the pattern in the RLE format.
+    ///
+    /// Dead cells at the end of rows and empty rows at the end of the pattern
+    /// are omitted, as allowed by the format.
+    pub fn to_rle(&self) -> String {
+        let mut text = format!("x = {}, y = {}", self.width, self.height);
+        if let Some(rule) = &self.rule {
+            text.push_str(", rule = ");
+            text.push_str(rule);
+        }
+        text.push('\n');
+
+        let mut line = String::new();
+        let mut push_run = |line: &mut String, count: u32, tag: char| {
+            let run = if count == 1 {
+                tag.to_string()
+            } else {
+                format!("{}{}", count, tag)
+            };
+            if line.len() + run.len() > RLE_LINE_LENGTH {
+                text.push_str(line);
+                text.push('\n');
+                line.clear();
+            }
+            line.push_str(&run);
+        };
+
+        let mut pending_rows = 0;
+        for row in self.rows() {
+            let len = row.iter().rposition(|&cell| cell == Cell::Alive).map_or(0, |col| col + 1);
+            if len == 0 {
+                pending_rows += 1;
+                continue;
+            }
+            if pending_rows > 0 {
+                push_run(&mut line, pending_rows, '$');
+            }
+            pending_rows = 1;
+            let mut cells = &row[..len];
+            while let Some(&cell) = cells.first() {
+                let run = cells.iter().take_while(|&&other| other == cell).count();
+                let tag = if cell == Cell::Alive { 'o' } else { 'b' };
+                push_run(&mut line, run as u32, tag);
+                cells = &cells[run..];
+            }
+        }
+        push_run(&mut line, 1, '!');
+        text.push_str(&line);
+        text.push('\n');
+        text
+    }
+
+    /// Write the pattern in the plaintext format, with `.` for dead cells and
+    /// `O` for live ones.
+    pub fn to_plaintext(&self) -> String {
+        let mut text = String::with_capacity(self.cells.len() + self.height as usize);
+        for row in self.rows() {
+            text.extend(row.iter().map(|&cell| if cell == Cell::Alive { 'O' } else { '.' }));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Returns the height of the pattern.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the width of the pattern.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the rule the pattern was designed for, if known.
+    pub fn rule(&self) -> Option<String> {
+        self.rule.clone()
+    }
+
+    /// Set the rule the pattern was designed for, written in RLE files.
+    pub fn set_rule(&mut self, rule: Option<String>) {
+        self.rule = rule;
+    }
+
+    /// Returns the number of live cells of the pattern.
+    pub fn population(&self) -> u32 {
+        self.cells.iter().filter(|&&cell| cell == Cell::Alive).count() as u32
+    }
+
+    /// Returns the pattern turned a quarter clockwise.
+    pub fn rotate_clockwise(&self) -> Pattern {
+        self.transform(self.width, self.height, |row, col| (self.height - 1 - col, row))
+    }
+
+    /// Returns the pattern turned a quarter counterclockwise.
+    pub fn rotate_counterclockwise(&self) -> Pattern {
+        self.transform(self.width, self.height, |row, col| (col, self.width - 1 - row))
+    }
+
+    /// Returns the pattern mirrored from left to right.
+    pub fn flip_horizontally(&self) -> Pattern {
+        self.transform(self.height, self.width, |row, col| (row, self.width - 1 - col))
+    }
+
+    /// Returns the pattern mirrored from top to bottom.
+    pub fn flip_vertically(&self) -> Pattern {
+        self.transform(self.height, self.width, |row, col| (self.height - 1 - row, col))
+    }
+
+    /// Returns the pattern where live cells are dead and dead ones alive.
+    pub fn invert(&self) -> Pattern {
+        let cells = self.cells.iter()
+            .map(|&cell| if cell == Cell::Alive { Cell::Dead } else { Cell::Alive })
+            .collect();
+        Pattern { cells, ..self.clone() }
+    }
+}
+
+impl Pattern {
+    /// Returns the state of the cell at the given row and column.
+    ///
+    /// Panics if it is out of the pattern's boundaries.
+    pub fn get(&self, row: u32, column: u32) -> Cell {
+        assert!(row < self.height && column < self.width, "cell out of the pattern");
+        self.cells[self.index(row, column)]
+    }
+
+    /// Set the state of the cell at the given row and column.
+    ///
+    /// Panics if it is out of the pattern's boundaries.
+    pub fn set(&mut self, row: u32, column: u32, cell: Cell) {
+        assert!(row < self.height && column < self.width, "cell out of the pattern");
+        let idx = self.index(row, column);
+        self.cells[idx] = cell;
+    }
+
+    /// Returns the cells of the pattern, in row then column order.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    /// Returns the cells of each row of the pattern.
+    pub(crate) fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks_exact(self.width.max(1) as usize)
+    }
+
+    /// Returns a pattern of the given dimensions, with the same rule, whose
+    /// cell at each row and column is the one of this pattern at the row and
+    /// column given by `source`.
+    fn transform(
+        &self,
+        height: u32,
+        width: u32,
+        source: impl Fn(u32, u32) -> (u32, u32)
+    ) -> Pattern {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for row in 0..height {
+            for col in 0..width {
+                let (source_row, source_col) = source(row, col);
+                cells.push(self.cells[self.index(source_row, source_col)]);
+            }
+        }
+        Pattern { height, width, cells, rule: self.rule.clone() }
+    }
+}
+
+/// Parse the `x = <width>, y = <height>[, rule = <rule>]` header of a RLE file
+/// into an empty pattern of those dimensions.
+///
+/// Returns `None` if the header is invalid.
+fn parse_rle_header(header: &str) -> Option<Result<Pattern, PatternError>> {
+    let (mut width, mut height, mut rule) = (None, None, None);
+    for field in header.split(',') {
+        let (key, value) = field.split_once('=')?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = Some(value.parse::<u32>().ok()?),
+            "y" => height = Some(value.parse::<u32>().ok()?),
+            "rule" => rule = Some(value.to_owned()),
+            _ => return None,
+        }
+    }
+    Some(Pattern::new(height?, width?).map(|pattern| Pattern { rule, ..pattern }))
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Create a Universe of the dimensions of the given pattern, with the
+    /// same cells.
+    pub fn from_pattern(pattern: &Pattern) -> Result<Universe, UniverseError> {
+        let mut universe = Universe::try_new(pattern.height, pattern.width)?;
+        universe.paste_pattern(pattern, 0, 0)?;
+        Ok(universe)
+    }
+
+    /// Copy the cells of the given pattern into the Universe, its top-left
+    /// corner being at the given row and column.
+    ///
+    /// Returns an error without updating any cell if the pattern does not fit
+    /// in the Universe there.
+    pub fn paste_pattern(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32
+    ) -> Result<(), UniverseError> {
+        self.paste(pattern, row, column, PasteMode::Overwrite)
+    }
+
+    /// Returns a pattern of the current generation of the whole Universe.
+    pub fn to_pattern(&self) -> Pattern {
+        Pattern {
+            height: self.height,
+            width: self.width,
+            cells: self.get_cells(),
+            rule: None,
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Writing and reading of PNG files, to share images of Universes and to
+//! draw patterns in image editors.
+//!
+//! Images are written with a palette, which is all a rendered Universe needs.
+//! Every kind of non-interlaced image can be read.
+
+use wasm_bindgen::prelude::*;
+use crate::crc::{crc32, Crc32};
+use crate::deflate::{zlib_compress, zlib_decompress};
+use crate::{Cell, PngError, Universe, UniverseError};
+
+/// First bytes of every PNG file.
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Colour types of PNG images.
+const GRAYSCALE: u8 = 0;
+const RGB: u8 = 2;
+const INDEXED: u8 = 3;
+const GRAYSCALE_ALPHA: u8 = 4;
+const RGBA: u8 = 6;
+
+/// Maximum number of pixels of a decoded image, each becoming a cell, so that
+/// a small file declaring huge dimensions cannot make the decoder allocate
+/// gigabytes.
+const MAX_PIXELS: u64 = 1 << 24;
+
+/// Write a PNG file of an image of `width` by `height` pixels, given as the
+/// index of the colour of each pixel in `palette`, in row then column order.
+/// The palette has at most 256 RGBA colours.
+pub(crate) fn encode(width: u32, height: u32, palette: &[[u8; 4]], indices: &[u8]) -> Vec<u8> {
+    let mut png = SIGNATURE.to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per pixel, default compression and filters, no interlacing.
+    header.extend_from_slice(&[8, INDEXED, 0, 0, 0]);
+    write_chunk(&mut png, b"IHDR", &header);
+
+    let colors: Vec<u8> = palette.iter().flat_map(|color| color[..3].to_vec()).collect();
+    write_chunk(&mut png, b"PLTE", &colors);
+    if palette.iter().any(|color| color[3] != 0xFF) {
+        let alphas: Vec<u8> = palette.iter().map(|color| color[3]).collect();
+        write_chunk(&mut png, b"tRNS", &alphas);
+    }
+
+    // Each row is preceded by its filter, none being used.
+    let mut scanlines = Vec::with_capacity(indices.len() + height as usize);
+    for row in indices.chunks_exact(width.max(1) as usize) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+    write_chunk(&mut png, b"IDAT", &zlib_compress(&scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Append a chunk of the given type and content to a PNG file.
+fn write_chunk(png: &mut Vec<u8>, chunk: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(chunk);
+    png.extend_from_slice(data);
+    let mut crc = Crc32::new();
+    crc.update(chunk);
+    crc.update(data);
+    png.extend_from_slice(&crc.finish().to_be_bytes());
+}
+
+/// Description of an image, from the `IHDR` chunk of its PNG file.
+struct Header {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    /// Parse the content of an `IHDR` chunk.
+    fn parse(data: &[u8]) -> Result<Header, PngError> {
+        if data.len() != 13 {
+            return Err(PngError::InvalidHeader);
+        }
+        let header = Header {
+            width: u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+            height: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            bit_depth: data[8],
+            color_type: data[9],
+        };
+        let valid_depth = match header.color_type {
+            GRAYSCALE => [1, 2, 4, 8, 16].contains(&header.bit_depth),
+            INDEXED => [1, 2, 4, 8].contains(&header.bit_depth),
+            RGB | GRAYSCALE_ALPHA | RGBA => [8, 16].contains(&header.bit_depth),
+            _ => false,
+        };
+        let (compression, filter, interlace) = (data[10], data[11], data[12]);
+        if !valid_depth || header.width == 0 || header.height == 0 ||
+            compression != 0 || filter != 0 || interlace > 1 {
+            return Err(PngError::InvalidHeader);
+        }
+        if interlace == 1 {
+            return Err(PngError::Interlaced);
+        }
+        Ok(header)
+    }
+
+    /// Returns the number of samples of each pixel.
+    fn channels(&self) -> u64 {
+        match self.color_type {
+            RGB => 3,
+            GRAYSCALE_ALPHA => 2,
+            RGBA => 4,
+            _ => 1,
+        }
+    }
+
+    /// Returns the number of bits of each pixel.
+    fn bits_per_pixel(&self) -> u64 {
+        self.channels() * u64::from(self.bit_depth)
+    }
+
+    /// Returns the number of bytes of each row of pixels, without its filter.
+    fn row_len(&self) -> u64 {
+        (u64::from(self.width) * self.bits_per_pixel()).div_ceil(8)
+    }
+}
+
+/// Pixels of a decoded PNG file.
+struct Image {
+    header: Header,
+
+    /// RGBA colours of an indexed image.
+    palette: Vec<[u8; 4]>,
+
+    /// Unfiltered rows of pixels, as stored in the file.
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Decode a PNG file.
+    fn decode(bytes: &[u8]) -> Result<Image, PngError> {
+        if !bytes.starts_with(&SIGNATURE) {
+            return Err(PngError::InvalidSignature);
+        }
+        let mut header = None;
+        let mut palette = Vec::new();
+        let mut compressed = Vec::new();
+        let mut rest = &bytes[SIGNATURE.len()..];
+        loop {
+            if rest.len() < 12 {
+                return Err(PngError::Truncated);
+            }
+            let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            if rest.len() - 12 < len {
+                return Err(PngError::Truncated);
+            }
+            let chunk = [rest[4], rest[5], rest[6], rest[7]];
+            let data = &rest[8..8 + len];
+            let crc = &rest[8 + len..12 + len];
+            if crc32(&rest[4..8 + len]).to_be_bytes() != crc {
+                return Err(PngError::CorruptChunk { chunk });
+            }
+            rest = &rest[12 + len..];
+
+            match &chunk {
+                b"IHDR" => header = Some(Header::parse(data)?),
+                _ if header.is_none() => return Err(PngError::InvalidHeader),
+                b"PLTE" => {
+                    palette = data.chunks_exact(3)
+                        .map(|color| [color[0], color[1], color[2], 0xFF])
+                        .collect();
+                }
+                b"tRNS" => {
+                    for (color, &alpha) in palette.iter_mut().zip(data) {
+                        color[3] = alpha;
+                    }
+                }
+                b"IDAT" => compressed.extend_from_slice(data),
+                b"IEND" => break,
+                _ => {}
+            }
+        }
+
+        let header = header.ok_or(PngError::InvalidHeader)?;
+        if u64::from(header.height) * u64::from(header.width) > MAX_PIXELS {
+            return Err(PngError::Universe(UniverseError::ImageTooLarge {
+                height: header.height,
+                width: header.width,
+            }));
+        }
+        let len = u64::from(header.height) * (header.row_len() + 1);
+        let scanlines = zlib_decompress(&compressed, len as usize)
+            .filter(|scanlines| scanlines.len() as u64 == len)
+            .ok_or(PngError::InvalidData)?;
+        let data = unfilter(&header, &scanlines).ok_or(PngError::InvalidData)?;
+        Ok(Image { header, palette, data })
+    }
+
+    /// Returns the luminance of the pixel at the given row and column, from
+    /// `0` for black to `255` for white, transparent pixels being considered
+    /// white.
+    fn luminance(&self, row: u32, column: u32) -> Result<u8, PngError> {
+        let sample = |channel: u64| self.sample(row, column, channel);
+        let (luminance, alpha) = match self.header.color_type {
+            GRAYSCALE => (sample(0), 0xFF),
+            GRAYSCALE_ALPHA => (sample(0), sample(1)),
+            RGB => (luminance(sample(0), sample(1), sample(2)), 0xFF),
+            RGBA => (luminance(sample(0), sample(1), sample(2)), sample(3)),
+            _ => {
+                let color = self.palette.get(usize::from(sample(0)))
+                    .ok_or(PngError::InvalidData)?;
+                (luminance(color[0], color[1], color[2]), color[3])
+            }
+        };
+        let (luminance, alpha) = (u32::from(luminance), u32::from(alpha));
+        Ok(((luminance * alpha + 0xFF * (0xFF - alpha)) / 0xFF) as u8)
+    }
+
+    /// Returns the value of a channel of the pixel at the given row and
+    /// column, on 8 bits, or the index of its colour for indexed images.
+    fn sample(&self, row: u32, column: u32, channel: u64) -> u8 {
+        let header = &self.header;
+        let row_start = u64::from(row) * header.row_len();
+        let depth = u64::from(header.bit_depth);
+        let bit = (u64::from(column) * header.channels() + channel) * depth;
+        let byte = self.data[(row_start + bit / 8) as usize];
+        if depth >= 8 {
+            // Only the most significant byte of 16-bit samples is kept.
+            return byte;
+        }
+        let max = (1u8 << depth) - 1;
+        let value = byte >> (8 - depth - bit % 8) & max;
+        if header.color_type == INDEXED {
+            value
+        } else {
+            (u32::from(value) * 0xFF / u32::from(max)) as u8
+        }
+    }
+}
+
+/// Returns the luminance of a colour, from `0` for black to `255` for white.
+fn luminance(red: u8, green: u8, blue: u8) -> u8 {
+    ((299 * u32::from(red) + 587 * u32::from(green) + 114 * u32::from(blue)) / 1000) as u8
+}
+
+/// Reverse the filters applied to the rows of an image, preceded by their
+/// filter type.
+/// Returns `None` if a filter type is unknown.
+fn unfilter(header: &Header, scanlines: &[u8]) -> Option<Vec<u8>> {
+    let row_len = header.row_len() as usize;
+    let pixel_len = header.bits_per_pixel().div_ceil(8) as usize;
+    let mut data = vec![0; header.height as usize * row_len];
+    for (row, scanline) in scanlines.chunks_exact(row_len + 1).enumerate() {
+        let (filter, filtered) = (scanline[0], &scanline[1..]);
+        let (previous_rows, current_rows) = data.split_at_mut(row * row_len);
+        let current = &mut current_rows[..row_len];
+        let up = if row == 0 { None } else { Some(&previous_rows[(row - 1) * row_len..]) };
+        for i in 0..row_len {
+            let left = if i >= pixel_len { current[i - pixel_len] } else { 0 };
+            let above = up.map_or(0, |up| up[i]);
+            let upper_left = match up {
+                Some(up) if i >= pixel_len => up[i - pixel_len],
+                _ => 0,
+            };
+            let predictor = match filter {
+                0 => 0,
+                1 => left,
+                2 => above,
+                3 => ((u16::from(left) + u16::from(above)) / 2) as u8,
+                4 => paeth(left, above, upper_left),
+                _ => return None,
+            };
+            current[i] = filtered[i].wrapping_add(predictor);
+        }
+    }
+    Some(data)
+}
+
+/// Returns the neighbouring byte closest to `left + above - upper_left`.
+fn paeth(left: u8, above: u8, upper_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(above) - i16::from(upper_left);
+    let distance_left = (estimate - i16::from(left)).abs();
+    let distance_above = (estimate - i16::from(above)).abs();
+    let distance_upper_left = (estimate - i16::from(upper_left)).abs();
+    if distance_left <= distance_above && distance_left <= distance_upper_left {
+        left
+    } else if distance_above <= distance_upper_left {
+        above
+    } else {
+        upper_left
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Create a Universe from a PNG image, each pixel becoming a cell which
+    /// is alive if its luminance, from `0` for black to `255` for white, is
+    /// lower than `threshold`. Transparent pixels are considered white.
+    ///
+    /// An image written by a `Framebuffer` with cells of one pixel and no
+    /// grid lines gives back the Universe it was rendered from.
+    /// Images of more than 2^24 pixels are rejected, before their pixels are
+    /// decoded.
+    pub fn from_png(bytes: &[u8], threshold: u8) -> Result<Universe, PngError> {
+        let image = Image::decode(bytes)?;
+        let mut universe = Universe::try_new(image.header.height, image.header.width)?;
+        for row in 0..universe.height {
+            for col in 0..universe.width {
+                if image.luminance(row, col)? < threshold {
+                    let idx = universe.get_index(row, col);
+                    universe.current_cells[idx] = Cell::Alive;
+                }
+            }
+        }
+        universe.active_tiles.invalidate();
+        Ok(universe)
+    }
+}
@@ -0,0 +1,257 @@
+//! Edition of rectangular regions of a Universe: copying them as patterns,
+//! pasting patterns back in several ways, and transforming them in place.
+//!
+//! A region is given by its first row and column, and its height and width.
+//! Copied regions are patterns, which can be exchanged as text through
+//! `Pattern::to_rle` and `Pattern::parse`.
+
+use wasm_bindgen::prelude::*;
+use crate::{Cell, Pattern, Universe, UniverseError};
+
+/// How the cells of a pasted pattern are combined with the ones of the
+/// Universe.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasteMode {
+    /// Cells of the Universe are replaced by the ones of the pattern.
+    Overwrite,
+    /// Cells are alive if they are alive in the Universe or in the pattern.
+    Or,
+    /// Cells of the Universe are toggled where the pattern is alive.
+    Xor,
+    /// Cells stay alive only where the pattern is alive.
+    And,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Returns a pattern of the cells of the given region.
+    ///
+    /// Returns an error if the region is not entirely in the Universe.
+    pub fn copy_region(
+        &self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<Pattern, UniverseError> {
+        self.check_region(row, column, height, width)?;
+        let mut pattern = Pattern::new(height, width)
+            .map_err(|_| UniverseError::TooLarge { height, width })?;
+        for pattern_row in 0..height {
+            let start = self.get_index(row + pattern_row, column);
+            for (pattern_col, &cell) in (0..).zip(&self.current_cells[start..start + width as usize]) {
+                pattern.set(pattern_row, pattern_col, cell);
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// Combine the cells of the given pattern with the ones of the Universe
+    /// according to `mode`, its top-left corner being at the given row and
+    /// column.
+    ///
+    /// Returns an error without updating any cell if the pattern does not fit
+    /// in the Universe there.
+    pub fn paste(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+        mode: PasteMode
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.paste_cells(pattern, row, column, mode)
+    }
+
+    /// Turn the given region a quarter clockwise, around its top-left corner:
+    /// the region becomes `width` rows high and `height` columns wide, and
+    /// the cells it leaves are cleared.
+    ///
+    /// Returns an error and do nothing if either the region or the turned one
+    /// is not entirely in the Universe.
+    pub fn rotate_region_clockwise(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.check_region(row, column, width, height)?;
+        let pattern = self.copy_region(row, column, height, width)?;
+        self.update_region(row, column, height, width, |_| Cell::Dead)?;
+        self.paste_cells(&pattern.rotate_clockwise(), row, column, PasteMode::Overwrite)
+    }
+
+    /// Turn the given region a quarter counterclockwise, around its top-left
+    /// corner, as `rotate_region_clockwise` does.
+    pub fn rotate_region_counterclockwise(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.check_region(row, column, width, height)?;
+        let pattern = self.copy_region(row, column, height, width)?;
+        self.update_region(row, column, height, width, |_| Cell::Dead)?;
+        self.paste_cells(&pattern.rotate_counterclockwise(), row, column, PasteMode::Overwrite)
+    }
+
+    /// Mirror the given region from left to right.
+    ///
+    /// Returns an error and do nothing if the region is not entirely in the
+    /// Universe.
+    pub fn flip_region_horizontally(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.check_region(row, column, height, width)?;
+        for region_row in row..row + height {
+            let start = self.get_index(region_row, column);
+            self.current_cells[start..start + width as usize].reverse();
+        }
+        self.active_tiles.invalidate();
+        Ok(())
+    }
+
+    /// Mirror the given region from top to bottom.
+    ///
+    /// Returns an error and do nothing if the region is not entirely in the
+    /// Universe.
+    pub fn flip_region_vertically(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        let pattern = self.copy_region(row, column, height, width)?;
+        self.paste_cells(&pattern.flip_vertically(), row, column, PasteMode::Overwrite)
+    }
+
+    /// Make the live cells of the given region dead and the dead ones alive.
+    ///
+    /// Returns an error and do nothing if the region is not entirely in the
+    /// Universe.
+    pub fn invert_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.update_region(row, column, height, width, |cell| match cell {
+            Cell::Dead => Cell::Alive,
+            Cell::Alive => Cell::Dead,
+        })
+    }
+
+    /// Make every cell of the given region dead.
+    ///
+    /// Returns an error and do nothing if the region is not entirely in the
+    /// Universe.
+    pub fn clear_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.update_region(row, column, height, width, |_| Cell::Dead)
+    }
+
+    /// Make every cell of the given region alive.
+    ///
+    /// Returns an error and do nothing if the region is not entirely in the
+    /// Universe.
+    pub fn fill_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        self.finish_pending_tick();
+        self.update_region(row, column, height, width, |_| Cell::Alive)
+    }
+}
+
+impl Universe {
+    /// Returns an error if the given region is not entirely in the Universe.
+    /// Empty regions are always valid.
+    fn check_region(
+        &self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32
+    ) -> Result<(), UniverseError> {
+        if height == 0 || width == 0 {
+            return Ok(());
+        }
+        self.check_bounds(row.saturating_add(height - 1), column.saturating_add(width - 1))
+    }
+
+    /// Combine the cells of the given pattern with the ones of the Universe,
+    /// as `paste` does.
+    fn paste_cells(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+        mode: PasteMode
+    ) -> Result<(), UniverseError> {
+        self.check_region(row, column, pattern.height(), pattern.width())?;
+        if pattern.height() == 0 || pattern.width() == 0 {
+            return Ok(());
+        }
+        for (pattern_row, pattern_cells) in (row..).zip(pattern.rows()) {
+            let start = self.get_index(pattern_row, column);
+            let cells = &mut self.current_cells[start..start + pattern_cells.len()];
+            for (cell, &pattern_cell) in cells.iter_mut().zip(pattern_cells) {
+                *cell = match (mode, pattern_cell) {
+                    (PasteMode::Overwrite, _) => pattern_cell,
+                    (PasteMode::Or, Cell::Alive) => Cell::Alive,
+                    (PasteMode::Xor, Cell::Alive) => match *cell {
+                        Cell::Dead => Cell::Alive,
+                        Cell::Alive => Cell::Dead,
+                    },
+                    (PasteMode::And, Cell::Dead) => Cell::Dead,
+                    _ => *cell,
+                };
+            }
+        }
+        self.active_tiles.invalidate();
+        Ok(())
+    }
+
+    /// Replace each cell of the given region by the result of `update`.
+    fn update_region(
+        &mut self,
+        row: u32,
+        column: u32,
+        height: u32,
+        width: u32,
+        update: impl Fn(Cell) -> Cell
+    ) -> Result<(), UniverseError> {
+        self.check_region(row, column, height, width)?;
+        for region_row in row..row + height {
+            let start = self.get_index(region_row, column);
+            for cell in &mut self.current_cells[start..start + width as usize] {
+                *cell = update(*cell);
+            }
+        }
+        self.active_tiles.invalidate();
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+/// Small seedable pseudo-random number generator, based on SplitMix64.
+///
+/// It is not cryptographically secure, but it is fast, has a good enough
+/// distribution for simulations and - most importantly - always produces the
+/// same sequence of numbers for the same seed, on every platform.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a new `Rng` whose sequence is entirely determined by `seed`.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Returns the current state of the generator, from which `Rng::new`
+    /// continues the same sequence.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Generate the next pseudo-random 64 bits number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a pseudo-random floating number in the `[0, 1)` range.
+    pub fn next_f64(&mut self) -> f64 {
+        // Only keep the 53 bits which can be represented in the mantissa
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns `true` with the given probability.
+    ///
+    /// No number is generated when the probability is either `0` or `1`, so
+    /// that deterministic settings do not advance the sequence.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        if probability >= 1. {
+            true
+        } else if probability <= 0. {
+            false
+        } else {
+            self.next_f64() < probability
+        }
+    }
+}
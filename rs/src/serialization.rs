@@ -0,0 +1,194 @@
+//! Serialization of Universes and their settings with `serde`, so they can be
+//! stored as JSON or any other format supported by `serde`, and converted to
+//! and from JavaScript objects.
+//!
+//! A Universe is serialized as a structure with the following fields:
+//!
+//! | Field                  | Content                                          |
+//! |------------------------|--------------------------------------------------|
+//! | `height`, `width`      | Dimensions                                       |
+//! | `topology`             | `"Torus"` or `"Bounded"`                         |
+//! | `generation`           | Generation, `0` if missing                       |
+//! | `margolus_rule`        | Table of the Margolus rule, or `null`            |
+//! | `birth_probability`    | Birth probability, `1` if missing                |
+//! | `survival_probability` | Survival probability, `1` if missing             |
+//! | `noise_rate`           | Noise rate, `0` if missing                       |
+//! | `rng_state`            | State of the random number generator, in hex     |
+//! | `cells`                | Cells, in base64                                 |
+//!
+//! Cells are encoded in row then column order with one bit per cell, set for
+//! live cells, from the least significant bit of each byte, as in snapshots.
+//! An empty string stands for a Universe where every cell is dead.
+//! The state of the random number generator is a string as JavaScript
+//! numbers cannot hold every 64 bits integer.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use wasm_bindgen::prelude::*;
+use crate::rng::Rng;
+use crate::snapshot::{decode_bits, encode_bits};
+use crate::{Cell, MargolusRule, Topology, Universe};
+
+/// Characters of the base64 encoding, for each 6 bits value.
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Serialized form of a Universe.
+#[derive(Serialize, Deserialize)]
+struct UniverseData {
+    height: u32,
+    width: u32,
+    #[serde(default = "default_topology")]
+    topology: Topology,
+    #[serde(default)]
+    generation: u32,
+    #[serde(default)]
+    margolus_rule: Option<MargolusRule>,
+    #[serde(default = "default_probability")]
+    birth_probability: f64,
+    #[serde(default = "default_probability")]
+    survival_probability: f64,
+    #[serde(default)]
+    noise_rate: f64,
+    #[serde(default)]
+    rng_state: String,
+    #[serde(default)]
+    cells: String,
+}
+
+fn default_topology() -> Topology {
+    Topology::Torus
+}
+
+fn default_probability() -> f64 {
+    1.
+}
+
+impl Serialize for MargolusRule {
+    /// Serialize the rule as its 16-entry block table.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.table().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MargolusRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<MargolusRule, D::Error> {
+        let table = Vec::<u8>::deserialize(deserializer)?;
+        MargolusRule::new(&table)
+            .ok_or_else(|| D::Error::custom("invalid Margolus table"))
+    }
+}
+
+impl Serialize for Universe {
+    /// Serialize the whole state of the Universe, like `to_snapshot`.
+    /// Tracking settings, ages and heat map are not serialized.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cells = self.get_cells();
+        let cells = if cells.contains(&Cell::Alive) {
+            encode_base64(&encode_bits(&cells))
+        } else {
+            String::new()
+        };
+        UniverseData {
+            height: self.height,
+            width: self.width,
+            topology: self.topology,
+            generation: self.generation,
+            margolus_rule: self.margolus_rule,
+            birth_probability: self.birth_probability,
+            survival_probability: self.survival_probability,
+            noise_rate: self.noise_rate,
+            rng_state: format!("{:016x}", self.rng.state()),
+            cells,
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Universe, D::Error> {
+        let data = UniverseData::deserialize(deserializer)?;
+        let mut universe = Universe::try_new(data.height, data.width).map_err(D::Error::custom)?;
+        universe.topology = data.topology;
+        universe.generation = data.generation;
+        if let Some(rule) = data.margolus_rule {
+            universe.set_margolus(rule).map_err(D::Error::custom)?;
+        }
+        for &probability in &[data.birth_probability, data.survival_probability, data.noise_rate] {
+            if !(0. ..=1.).contains(&probability) {
+                return Err(D::Error::custom("probabilities must be between 0 and 1"));
+            }
+        }
+        universe.birth_probability = data.birth_probability;
+        universe.survival_probability = data.survival_probability;
+        universe.noise_rate = data.noise_rate;
+        if !data.rng_state.is_empty() {
+            let state = u64::from_str_radix(&data.rng_state, 16)
+                .map_err(|_| D::Error::custom("invalid random number generator state"))?;
+            universe.rng = Rng::new(state);
+        }
+
+        if !data.cells.is_empty() {
+            let width = data.width as usize;
+            let cells = decode_base64(&data.cells)
+                .and_then(|bytes| decode_bits(&bytes, data.height as usize * width))
+                .ok_or_else(|| D::Error::custom("invalid cells"))?;
+            for (row, row_cells) in cells.chunks_exact(width).enumerate() {
+                let start = universe.get_index(row as u32, 0);
+                universe.current_cells[start..start + width].copy_from_slice(row_cells);
+            }
+            universe.active_tiles.invalidate();
+        }
+        Ok(universe)
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Returns the whole state of the Universe as a JavaScript object,
+    /// such as one which can be stored with `JSON.stringify`.
+    pub fn to_js_value(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(self)?)
+    }
+
+    /// Create a Universe from a JavaScript object returned by `to_js_value`.
+    pub fn from_js_value(value: JsValue) -> Result<Universe, JsValue> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// Encode bytes in base64, with padding.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter()
+            .enumerate()
+            .fold(0u32, |group, (idx, &byte)| group | u32::from(byte) << (16 - 8 * idx));
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                encoded.push(char::from(BASE64_ALPHABET[(group >> (18 - 6 * idx) & 0x3F) as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decode base64 text, with or without padding.
+/// Returns `None` if it is not valid base64.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        let mut group = 0u32;
+        for (idx, &character) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|&c| c == character)?;
+            group |= (value as u32) << (18 - 6 * idx);
+        }
+        bytes.extend(group.to_be_bytes()[1..chunk.len()].iter());
+    }
+    Some(bytes)
+}
@@ -0,0 +1,258 @@
+//! Computation of the Game of Life's generations by multiple workers sharing
+//! the same memory, such as Web Workers when WebAssembly threads are
+//! available.
+//!
+//! A tick is then computed in three steps:
+//!   1. the thread owning the `Universe` calls `start_shared_tick`, which
+//!      splits the next generation into bands of rows and returns the
+//!      identifier of that job.
+//!   2. that identifier is sent to the workers, which all call
+//!      `run_shared_tick_bands` with it to compute bands until none is left.
+//!   3. the thread owning the `Universe` calls `finish_shared_tick`, which
+//!      computes the remaining bands itself, waits for the workers to finish
+//!      theirs and then makes the new generation the current one.
+//!
+//! Without WebAssembly threads (when `SharedArrayBuffer` is not available),
+//! no worker is needed: `finish_shared_tick` then computes every band itself.
+//!
+//! Pending ticks are registered with their identifier, so that workers never
+//! get to a tick which is already finished: unknown identifiers are ignored
+//! by `run_shared_tick_bands`, and `finish_shared_tick` unregisters the tick
+//! then waits for every worker computing its bands before freeing it.
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use wasm_bindgen::prelude::*;
+use crate::{compute_life_band, Cell, Universe};
+
+/// Identifiers of the pending ticks, with the address of their `SharedTick`.
+static PENDING_TICKS: Mutex<Vec<(u32, usize)>> = Mutex::new(Vec::new());
+
+/// Identifier of the next started tick.
+static NEXT_JOB: AtomicU32 = AtomicU32::new(1);
+
+/// Generation being computed by multiple workers.
+pub(crate) struct SharedTick {
+    /// Identifier of the tick, sent to the workers.
+    job: u32,
+
+    /// Pointer to the current generation of the Universe, with its ghost
+    /// border, which is not updated while the tick is pending.
+    cells_ptr: *const Cell,
+
+    /// Number of rows of the Universe.
+    height: u32,
+
+    /// Number of columns of the Universe.
+    width: u32,
+
+    /// Buffer where the next generation is written, taken from the Universe
+    /// during the tick.
+    next_cells: Vec<Cell>,
+
+    /// Pointer to the beginning of `next_cells`, through which each band is
+    /// written.
+    next_cells_ptr: *mut Cell,
+
+    /// Number of rows in a band, the last one may be smaller.
+    band_height: u32,
+
+    /// Number of bands to compute.
+    nb_bands: u32,
+
+    /// Indices and new states of the cells which changed in each band.
+    /// Each one is only written by the worker which computed the band.
+    band_changes: Vec<UnsafeCell<(Vec<u32>, Vec<Cell>)>>,
+
+    /// Index of the next band which has to be computed.
+    next_band: AtomicU32,
+
+    /// Number of bands already computed.
+    done_bands: AtomicU32,
+
+    /// Number of workers currently computing bands through
+    /// `run_shared_tick_bands`.
+    active_workers: AtomicU32,
+}
+
+impl SharedTick {
+    /// Claim and compute bands until there is none left.
+    fn run_bands(&self) {
+        let (height, width) = (self.height, self.width);
+
+        // Safe because the Universe's cells are not updated until every band
+        // is computed.
+        let stride = width + 2;
+        let cells = unsafe {
+            std::slice::from_raw_parts(self.cells_ptr, ((height + 2) * stride) as usize)
+        };
+        loop {
+            let band = self.next_band.fetch_add(1, Ordering::Relaxed);
+            if band >= self.nb_bands {
+                return;
+            }
+            let first_row = band * self.band_height;
+            let last_row = (first_row + self.band_height).min(height);
+            let band_len = ((last_row - first_row) * stride) as usize;
+
+            // Safe because each band is only claimed once and bands do not
+            // overlap.
+            let next_band = unsafe {
+                let band_ptr = self.next_cells_ptr.add(((first_row + 1) * stride) as usize);
+                std::slice::from_raw_parts_mut(band_ptr, band_len)
+            };
+            let changes = compute_life_band(cells, width, first_row, next_band);
+            unsafe {
+                *self.band_changes[band as usize].get() = changes;
+            }
+            self.done_bands.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// Returns `true` if every band has been computed.
+    fn is_done(&self) -> bool {
+        self.done_bands.load(Ordering::Acquire) >= self.nb_bands
+    }
+}
+
+/// Compute bands of the tick whose identifier was returned by
+/// `Universe::start_shared_tick`, until there is none left.
+///
+/// This is meant to be called by workers sharing the Universe's memory.
+/// Nothing is done if the given identifier is not the one of a pending tick,
+/// such as when `Universe::finish_shared_tick` was already called.
+#[wasm_bindgen]
+pub fn run_shared_tick_bands(job: u32) {
+    let shared_tick = {
+        let pending_ticks = lock_pending_ticks();
+        let address = match pending_ticks.iter().find(|&&(pending, _)| pending == job) {
+            Some(&(_, address)) => address,
+            None => return,
+        };
+
+        // Safe because a registered tick is not freed until it is
+        // unregistered by `finish_shared_tick`, which then waits for its
+        // active workers: this one is counted before the lock is released.
+        let shared_tick = unsafe { &*(address as *const SharedTick) };
+        shared_tick.active_workers.fetch_add(1, Ordering::Relaxed);
+        shared_tick
+    };
+    shared_tick.run_bands();
+    shared_tick.active_workers.fetch_sub(1, Ordering::Release);
+}
+
+/// Lock the registry of pending ticks.
+///
+/// This busy-waits, as blocking waits are forbidden on a browser's main
+/// thread, but the lock is only held for a few instructions.
+fn lock_pending_ticks() -> MutexGuard<'static, Vec<(u32, usize)>> {
+    loop {
+        match PENDING_TICKS.try_lock() {
+            Ok(pending_ticks) => return pending_ticks,
+            Err(TryLockError::Poisoned(err)) => return err.into_inner(),
+            Err(TryLockError::WouldBlock) => std::hint::spin_loop(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start computing the next generation by splitting it into `nb_bands`
+    /// bands of rows, which can then be computed by workers through
+    /// `run_shared_tick_bands`.
+    ///
+    /// Returns the identifier of that job to send to those workers.
+    /// Returns `0` if the tick could not be split, because the Universe does
+    /// not follow the Game of Life's rules or has stochastic transitions or
+    /// noise. In that case, the tick is computed right away.
+    ///
+    /// Until the tick is finished through `finish_shared_tick`, the methods
+    /// updating this Universe first finish it themselves.
+    pub fn start_shared_tick(&mut self, nb_bands: u32) -> u32 {
+        if self.margolus_rule.is_some() || !self.is_deterministic() {
+            self.tick();
+            return 0;
+        }
+        self.finish_shared_tick();
+        let nb_bands = nb_bands.clamp(1, self.height);
+        let band_height = self.height.div_ceil(nb_bands);
+        let nb_bands = self.height.div_ceil(band_height);
+        self.fill_ghost_border();
+        let mut next_cells = std::mem::take(&mut self.next_cells);
+        let next_cells_ptr = next_cells.as_mut_ptr();
+        let job = match NEXT_JOB.fetch_add(1, Ordering::Relaxed) {
+            0 => NEXT_JOB.fetch_add(1, Ordering::Relaxed),
+            job => job,
+        };
+        let shared_tick = Box::new(SharedTick {
+            job,
+            cells_ptr: self.current_cells.as_ptr(),
+            height: self.height,
+            width: self.width,
+            next_cells,
+            next_cells_ptr,
+            band_height,
+            nb_bands,
+            band_changes: (0..nb_bands).map(|_| UnsafeCell::default()).collect(),
+            next_band: AtomicU32::new(0),
+            done_bands: AtomicU32::new(0),
+            active_workers: AtomicU32::new(0),
+        });
+        let address = &*shared_tick as *const SharedTick as usize;
+        self.shared_tick = Some(shared_tick);
+        lock_pending_ticks().push((job, address));
+        job
+    }
+
+    /// Returns `true` if there is no pending tick started through
+    /// `start_shared_tick` or if all of its bands are computed, meaning that
+    /// `finish_shared_tick` will not have to wait.
+    pub fn is_shared_tick_done(&self) -> bool {
+        self.shared_tick.as_ref().is_none_or(|shared_tick| shared_tick.is_done())
+    }
+
+    /// Finish the tick started through `start_shared_tick`: compute the bands
+    /// not yet claimed by a worker, wait for the workers to finish theirs and
+    /// then make the new generation the current one.
+    ///
+    /// Do nothing if no tick is pending.
+    pub fn finish_shared_tick(&mut self) {
+        let shared_tick = match self.shared_tick.as_ref() {
+            Some(shared_tick) => shared_tick,
+            None => return,
+        };
+        shared_tick.run_bands();
+        lock_pending_ticks().retain(|&(job, _)| job != shared_tick.job);
+
+        // Remaining bands are being computed by workers, which should not be
+        // long. Busy-waiting is also the only possibility on a browser's main
+        // thread, where blocking waits are forbidden.
+        // No worker can start anymore, and once they are all done, every band
+        // is computed.
+        while shared_tick.active_workers.load(Ordering::Acquire) > 0 || !shared_tick.is_done() {
+            std::hint::spin_loop();
+        }
+        let shared_tick = *self.shared_tick.take().unwrap();
+        self.next_cells = shared_tick.next_cells;
+        std::mem::swap(&mut self.current_cells, &mut self.next_cells);
+        self.changed_cells.clear();
+        self.changed_states.clear();
+        for band_changes in shared_tick.band_changes {
+            let (changed_cells, changed_states) = band_changes.into_inner();
+            self.changed_cells.extend(changed_cells);
+            self.changed_states.extend(changed_states);
+        }
+        self.active_tiles.set_changes_from_cells(&self.changed_cells, self.width);
+        self.update_activity();
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+impl Drop for Universe {
+    /// Finish the pending tick, if any, as its workers read the cells of the
+    /// Universe.
+    fn drop(&mut self) {
+        self.finish_shared_tick();
+    }
+}
@@ -0,0 +1,290 @@
+//! Computation of the Game of Life's generations 16 cells at a time, through
+//! SIMD instructions.
+//!
+//! When targeting WebAssembly, this relies on the `simd128` proposal, which
+//! has to be enabled at compile time:
+//!
+//!   RUSTFLAGS='-C target-feature=+simd128' cargo build --features simd ...
+//!
+//! As a WebAssembly module using those instructions cannot even be loaded by
+//! a browser not supporting them, the JavaScript side should check for them
+//! (e.g. with `WebAssembly.validate`) before choosing between a SIMD and a
+//! regular build.
+//! On x86_64, SSE2 instructions are used instead, mostly so the same algorithm
+//! can be tested natively.
+//! On any other target, or for a WebAssembly build without `simd128`, ticks
+//! fall back to the regular, scalar, computation.
+
+use wasm_bindgen::prelude::*;
+use crate::{life_next_state, Cell, Universe};
+
+/// Returns `true` if this build computes ticks through SIMD instructions
+/// when `Universe::set_simd` enables them.
+///
+/// This is a build-time capability, not a detection of what the running
+/// platform supports: a WebAssembly build includes SIMD instructions only if
+/// it was compiled with the `simd128` target feature, and then cannot even be
+/// loaded where they are not supported. On x86_64, the SSE2 instructions used
+/// are part of every CPU.
+#[wasm_bindgen]
+pub fn simd_compiled() -> bool {
+    cfg!(any(all(target_arch = "wasm32", target_feature = "simd128"),
+             target_arch = "x86_64"))
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Enable or disable the computation of ticks through SIMD instructions,
+    /// when this build includes them, see `simd_compiled`. Disabled by
+    /// default.
+    ///
+    /// This has no effect on the result, only on performance. Note that SIMD
+    /// instructions are only used when following the Game of Life's rules,
+    /// without stochastic transitions nor noise. In that case, the tracking of
+    /// stable parts of the Universe is not used.
+    pub fn set_simd(&mut self, enabled: bool) {
+        self.finish_pending_tick();
+        self.simd = enabled;
+    }
+}
+
+impl Universe {
+    /// Calculate the next iteration of our Universe according to the Game of
+    /// Life's rules, by computing 16 cells at a time.
+    ///
+    /// /!\ SIMD instructions should be part of this build, see `simd_compiled`.
+    pub(crate) fn compute_life_generation_simd(&mut self) {
+        self.fill_ghost_border();
+        let width = self.width as usize;
+        let stride = width + 2;
+        let padded_rows = self.current_cells.windows(3 * stride).step_by(stride);
+        let next_rows = self.next_cells[stride..].chunks_mut(stride);
+        for (rows, next_row) in padded_rows.zip(next_rows) {
+            let (up, rows) = rows.split_at(stride);
+            let (row, down) = rows.split_at(stride);
+            compute_life_row(up, row, down, &mut next_row[1..=width]);
+        }
+        std::mem::swap(&mut self.current_cells, &mut self.next_cells);
+    }
+}
+
+/// Compute the next generation of a row of cells into `next_row`, based on its
+/// current state and the rows directly up and down.
+/// Those rows include the ghost cells on the left and right of the row, which
+/// `next_row` does not.
+fn compute_life_row(up: &[Cell], row: &[Cell], down: &[Cell], next_row: &mut [Cell]) {
+    let width = next_row.len();
+    let mut col = 0;
+    while col + lanes::LANES <= width {
+        // Safe because cells from `col` to `col + LANES + 1` are in the rows
+        // and because `Cell` is represented as a `u8` of `0` or `1`.
+        unsafe {
+            compute_life_lanes(up.as_ptr().add(col + 1),
+                               row.as_ptr().add(col + 1),
+                               down.as_ptr().add(col + 1),
+                               next_row.as_mut_ptr().add(col));
+        }
+        col += lanes::LANES;
+    }
+
+    // The remaining ones are computed one by one.
+    for (col, next_cell) in next_row.iter_mut().enumerate().skip(col) {
+        let live_neighbors = up[col] as u8 + up[col + 1] as u8 + up[col + 2] as u8 +
+            row[col] as u8 + row[col + 2] as u8 +
+            down[col] as u8 + down[col + 1] as u8 + down[col + 2] as u8;
+        *next_cell = life_next_state(row[col + 1], live_neighbors);
+    }
+}
+
+/// Compute the next generation of the `lanes::LANES` cells beginning at `row`.
+/// `up` and `down` point to the cells directly on top and at the bottom of it.
+///
+/// /!\ The cell before and after those lanes should be readable in each row.
+#[inline(always)]
+unsafe fn compute_life_lanes(
+    up: *const Cell,
+    row: *const Cell,
+    down: *const Cell,
+    next: *mut Cell
+) {
+    use lanes::U8x16;
+    let (up, row, down) = (up as *const u8, row as *const u8, down as *const u8);
+    let count = U8x16::load(up.sub(1))
+        .add(U8x16::load(up))
+        .add(U8x16::load(up.add(1)))
+        .add(U8x16::load(row.sub(1)))
+        .add(U8x16::load(row.add(1)))
+        .add(U8x16::load(down.sub(1)))
+        .add(U8x16::load(down))
+        .add(U8x16::load(down.add(1)));
+    let cells = U8x16::load(row);
+
+    // A cell is alive if it has 3 neighbours, or 2 if it was already alive.
+    let alive = count.eq(U8x16::splat(3))
+        .or(count.eq(U8x16::splat(2)).and(cells.eq(U8x16::splat(1))));
+    alive.and(U8x16::splat(1)).store(next as *mut u8);
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod lanes {
+    use core::arch::wasm32::*;
+
+    /// Number of cells processed at a time.
+    pub const LANES: usize = 16;
+
+    /// 16 `u8` values processed at once.
+    #[derive(Clone, Copy)]
+    pub struct U8x16(v128);
+
+    impl U8x16 {
+        #[inline(always)]
+        pub unsafe fn load(ptr: *const u8) -> U8x16 {
+            U8x16(v128_load(ptr as *const v128))
+        }
+
+        #[inline(always)]
+        pub unsafe fn store(self, ptr: *mut u8) {
+            v128_store(ptr as *mut v128, self.0)
+        }
+
+        #[inline(always)]
+        pub fn splat(value: u8) -> U8x16 {
+            U8x16(u8x16_splat(value))
+        }
+
+        #[inline(always)]
+        pub fn add(self, other: U8x16) -> U8x16 {
+            U8x16(u8x16_add(self.0, other.0))
+        }
+
+        /// Set each lane to `0xFF` if both values are equal, `0` otherwise.
+        #[inline(always)]
+        pub fn eq(self, other: U8x16) -> U8x16 {
+            U8x16(u8x16_eq(self.0, other.0))
+        }
+
+        #[inline(always)]
+        pub fn and(self, other: U8x16) -> U8x16 {
+            U8x16(v128_and(self.0, other.0))
+        }
+
+        #[inline(always)]
+        pub fn or(self, other: U8x16) -> U8x16 {
+            U8x16(v128_or(self.0, other.0))
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod lanes {
+    //! SSE2 is part of every x86_64 CPU, which makes those intrinsics always
+    //! safe to call.
+
+    use core::arch::x86_64::*;
+
+    /// Number of cells processed at a time.
+    pub const LANES: usize = 16;
+
+    /// 16 `u8` values processed at once.
+    #[derive(Clone, Copy)]
+    pub struct U8x16(__m128i);
+
+    impl U8x16 {
+        #[inline(always)]
+        pub unsafe fn load(ptr: *const u8) -> U8x16 {
+            U8x16(_mm_loadu_si128(ptr as *const __m128i))
+        }
+
+        #[inline(always)]
+        pub unsafe fn store(self, ptr: *mut u8) {
+            _mm_storeu_si128(ptr as *mut __m128i, self.0)
+        }
+
+        #[inline(always)]
+        pub fn splat(value: u8) -> U8x16 {
+            U8x16(unsafe { _mm_set1_epi8(value as i8) })
+        }
+
+        #[inline(always)]
+        pub fn add(self, other: U8x16) -> U8x16 {
+            U8x16(unsafe { _mm_add_epi8(self.0, other.0) })
+        }
+
+        /// Set each lane to `0xFF` if both values are equal, `0` otherwise.
+        #[inline(always)]
+        pub fn eq(self, other: U8x16) -> U8x16 {
+            U8x16(unsafe { _mm_cmpeq_epi8(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        pub fn and(self, other: U8x16) -> U8x16 {
+            U8x16(unsafe { _mm_and_si128(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        pub fn or(self, other: U8x16) -> U8x16 {
+            U8x16(unsafe { _mm_or_si128(self.0, other.0) })
+        }
+    }
+}
+
+/// Portable version of the SIMD operations, for targets where they are not
+/// available. It is never used to compute ticks, as `simd_compiled` then
+/// returns `false`, but allows the rest of the code to compile.
+#[cfg(not(any(all(target_arch = "wasm32", target_feature = "simd128"),
+              target_arch = "x86_64")))]
+mod lanes {
+    /// Number of cells processed at a time.
+    pub const LANES: usize = 16;
+
+    /// 16 `u8` values processed at once.
+    #[derive(Clone, Copy)]
+    pub struct U8x16([u8; LANES]);
+
+    impl U8x16 {
+        #[inline(always)]
+        pub unsafe fn load(ptr: *const u8) -> U8x16 {
+            U8x16(std::ptr::read_unaligned(ptr as *const [u8; LANES]))
+        }
+
+        #[inline(always)]
+        pub unsafe fn store(self, ptr: *mut u8) {
+            std::ptr::write_unaligned(ptr as *mut [u8; LANES], self.0)
+        }
+
+        #[inline(always)]
+        pub fn splat(value: u8) -> U8x16 {
+            U8x16([value; LANES])
+        }
+
+        #[inline(always)]
+        pub fn add(self, other: U8x16) -> U8x16 {
+            self.zip(other, |a, b| a.wrapping_add(b))
+        }
+
+        /// Set each lane to `0xFF` if both values are equal, `0` otherwise.
+        #[inline(always)]
+        pub fn eq(self, other: U8x16) -> U8x16 {
+            self.zip(other, |a, b| if a == b { 0xFF } else { 0 })
+        }
+
+        #[inline(always)]
+        pub fn and(self, other: U8x16) -> U8x16 {
+            self.zip(other, |a, b| a & b)
+        }
+
+        #[inline(always)]
+        pub fn or(self, other: U8x16) -> U8x16 {
+            self.zip(other, |a, b| a | b)
+        }
+
+        #[inline(always)]
+        fn zip(self, other: U8x16, op: impl Fn(u8, u8) -> u8) -> U8x16 {
+            let mut result = [0; LANES];
+            for (i, value) in result.iter_mut().enumerate() {
+                *value = op(self.0[i], other.0[i]);
+            }
+            U8x16(result)
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Binary snapshots of the whole state of a Universe, to save a long run and
+//! resume it later, in a browser or natively.
+//!
+//! A snapshot contains, with numbers in little-endian order:
+//!
+//! | Size | Content                                                        |
+//! |------|----------------------------------------------------------------|
+//! | 8    | Signature: `0x89`, `GOL`, `\r\n`, `0x1A`, `\n`                 |
+//! | 2    | Version of the format, currently `1`                           |
+//! | 4    | Height                                                         |
+//! | 4    | Width                                                          |
+//! | 1    | Topology: `0` for a torus, `1` for bounded edges               |
+//! | 4    | Generation                                                     |
+//! | 1    | Rule: `0` for the Game of Life, `1` for a Margolus rule        |
+//! | 16   | Table of the Margolus rule, zeroes for the Game of Life        |
+//! | 8    | Birth probability, as a `f64`                                  |
+//! | 8    | Survival probability, as a `f64`                               |
+//! | 8    | Noise rate, as a `f64`                                         |
+//! | 8    | State of the pseudo-random number generator                    |
+//! | 1    | Encoding of the cells: `0` for bits, `1` for runs              |
+//! | 4    | Length `n` of the cells                                        |
+//! | n    | Cells, in row then column order                                |
+//! | 4    | CRC-32 of all the previous bytes                               |
+//!
+//! Cells are encoded either as one bit per cell, set for live cells, from
+//! the least significant bit of each byte, or as the lengths of alternating
+//! runs of dead and live cells, beginning with dead ones. Each length is
+//! written in LEB128: 7 bits per byte from the least significant ones, the
+//! most significant bit of a byte being set if another byte follows.
+//! The shortest of both encodings is used.
+
+use std::convert::TryInto;
+use wasm_bindgen::prelude::*;
+use crate::crc::crc32;
+use crate::{check_dimensions, Cell, MargolusRule, SnapshotError, Topology, Universe};
+use crate::rng::Rng;
+
+/// First bytes of every snapshot. As in PNG files, the first byte is not
+/// ASCII and the line breaks reveal conversions of line endings.
+const SIGNATURE: [u8; 8] = [0x89, b'G', b'O', b'L', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Version of the format written.
+const VERSION: u16 = 1;
+
+/// Number of bytes before the cells.
+const HEADER_LEN: usize = 77;
+
+/// Encodings of the cells.
+const BITS: u8 = 0;
+const RUNS: u8 = 1;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Returns a snapshot of the whole state of the Universe: its
+    /// dimensions, topology, generation, rule, probabilities, the state of
+    /// its random number generator and its cells. `from_snapshot` creates a
+    /// Universe evolving exactly as this one would.
+    ///
+    /// Tracking settings, ages and heat map are not saved.
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut snapshot = SIGNATURE.to_vec();
+        snapshot.extend_from_slice(&VERSION.to_le_bytes());
+        snapshot.extend_from_slice(&self.height.to_le_bytes());
+        snapshot.extend_from_slice(&self.width.to_le_bytes());
+        snapshot.push(match self.topology {
+            Topology::Torus => 0,
+            Topology::Bounded => 1,
+        });
+        snapshot.extend_from_slice(&self.generation.to_le_bytes());
+        match self.margolus_rule {
+            Some(rule) => {
+                snapshot.push(1);
+                snapshot.extend_from_slice(rule.table());
+            }
+            None => snapshot.extend_from_slice(&[0; 17]),
+        }
+        for value in &[self.birth_probability, self.survival_probability, self.noise_rate] {
+            snapshot.extend_from_slice(&value.to_le_bytes());
+        }
+        snapshot.extend_from_slice(&self.rng.state().to_le_bytes());
+
+        let cells = self.get_cells();
+        let bits = encode_bits(&cells);
+        let runs = encode_runs(&cells);
+        let (encoding, data) = if runs.len() < bits.len() { (RUNS, runs) } else { (BITS, bits) };
+        snapshot.push(encoding);
+        snapshot.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        snapshot.extend_from_slice(&data);
+
+        let crc = crc32(&snapshot);
+        snapshot.extend_from_slice(&crc.to_le_bytes());
+        snapshot
+    }
+
+    /// Create a Universe from a snapshot returned by `to_snapshot`.
+    ///
+    /// Returns an error if the snapshot is truncated, corrupted, or was
+    /// written by a newer version of the format.
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Universe, SnapshotError> {
+        if !bytes.starts_with(&SIGNATURE) {
+            return Err(if SIGNATURE.starts_with(bytes) {
+                SnapshotError::Truncated
+            } else {
+                SnapshotError::InvalidSignature
+            });
+        }
+        let mut reader = Reader { bytes, position: SIGNATURE.len() };
+        let version = u16::from_le_bytes(reader.read()?);
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(SnapshotError::Truncated);
+        }
+        let data_len = u32::from_le_bytes(bytes[HEADER_LEN - 4..HEADER_LEN].try_into().unwrap());
+        let len = HEADER_LEN as u64 + u64::from(data_len) + 4;
+        if (bytes.len() as u64) < len {
+            return Err(SnapshotError::Truncated);
+        }
+        let (content, crc) = bytes[..len as usize].split_at(len as usize - 4);
+        if crc32(content).to_le_bytes() != crc || bytes.len() as u64 > len {
+            return Err(SnapshotError::CorruptData);
+        }
+
+        let height = u32::from_le_bytes(reader.read()?);
+        let width = u32::from_le_bytes(reader.read()?);
+        let topology = match reader.read::<1>()? {
+            [0] => Topology::Torus,
+            [1] => Topology::Bounded,
+            _ => return Err(SnapshotError::InvalidData),
+        };
+        let generation = u32::from_le_bytes(reader.read()?);
+        let is_margolus = reader.read::<1>()?;
+        let table: [u8; 16] = reader.read()?;
+        let margolus_rule = match is_margolus {
+            [0] => None,
+            [1] => Some(MargolusRule::new(&table).ok_or(SnapshotError::InvalidData)?),
+            _ => return Err(SnapshotError::InvalidData),
+        };
+        let mut probabilities = [0.; 3];
+        for probability in probabilities.iter_mut() {
+            *probability = f64::from_le_bytes(reader.read()?);
+            if !(0. ..=1.).contains(probability) {
+                return Err(SnapshotError::InvalidData);
+            }
+        }
+        let rng = Rng::new(u64::from_le_bytes(reader.read()?));
+
+        // The cells are checked to match the dimensions before the Universe is
+        // allocated, as a few bytes can declare billions of cells.
+        check_dimensions(height, width)?;
+        let encoding = reader.read::<1>()?[0];
+        let data = &content[HEADER_LEN..];
+        let nb_cells = height as usize * width as usize;
+        let cells = match encoding {
+            BITS => decode_bits(data, nb_cells),
+            RUNS => decode_runs(data, nb_cells),
+            _ => None,
+        };
+        let cells = cells.ok_or(SnapshotError::InvalidData)?;
+
+        let mut universe = Universe::try_new(height, width)?;
+        universe.topology = topology;
+        universe.generation = generation;
+        if let Some(rule) = margolus_rule {
+            universe.set_margolus(rule)?;
+        }
+        universe.birth_probability = probabilities[0];
+        universe.survival_probability = probabilities[1];
+        universe.noise_rate = probabilities[2];
+        universe.rng = rng;
+        for (row, row_cells) in cells.chunks_exact(width as usize).enumerate() {
+            let start = universe.get_index(row as u32, 0);
+            universe.current_cells[start..start + width as usize].copy_from_slice(row_cells);
+        }
+        universe.active_tiles.invalidate();
+        Ok(universe)
+    }
+}
+
+/// Reads the fields of a snapshot one after the other.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Read the next `N` bytes.
+    fn read<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+        let bytes = self.bytes.get(self.position..self.position + N)
+            .ok_or(SnapshotError::Truncated)?;
+        self.position += N;
+        Ok(bytes.try_into().unwrap())
+    }
+}
+
+/// Encode cells as one bit per cell.
+pub(crate) fn encode_bits(cells: &[Cell]) -> Vec<u8> {
+    cells.chunks(8)
+        .map(|byte_cells| {
+            byte_cells.iter()
+                .enumerate()
+                .filter(|&(_, &cell)| cell == Cell::Alive)
+                .fold(0, |byte, (bit, _)| byte | 1 << bit)
+        })
+        .collect()
+}
+
+/// Decode `nb_cells` cells encoded as one bit per cell.
+/// Returns `None` if there is not exactly enough bytes for them.
+pub(crate) fn decode_bits(data: &[u8], nb_cells: usize) -> Option<Vec<Cell>> {
+    if data.len() != nb_cells.div_ceil(8) {
+        return None;
+    }
+    Some((0..nb_cells)
+        .map(|idx| if data[idx / 8] >> (idx % 8) & 1 == 1 { Cell::Alive } else { Cell::Dead })
+        .collect())
+}
+
+/// Encode cells as the lengths of alternating runs of dead and live cells.
+fn encode_runs(cells: &[Cell]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut state = Cell::Dead;
+    let mut run_len: u64 = 0;
+    for &cell in cells {
+        if cell != state {
+            write_leb128(&mut data, run_len);
+            state = cell;
+            run_len = 0;
+        }
+        run_len += 1;
+    }
+    write_leb128(&mut data, run_len);
+    data
+}
+
+/// Decode `nb_cells` cells encoded as the lengths of alternating runs of
+/// dead and live cells.
+/// Returns `None` if the runs do not cover exactly that number of cells,
+/// which is checked before allocating them.
+fn decode_runs(mut data: &[u8], nb_cells: usize) -> Option<Vec<Cell>> {
+    let mut run_lens = Vec::new();
+    let mut total: u64 = 0;
+    while !data.is_empty() {
+        let run_len = read_leb128(&mut data)?;
+        total = total.checked_add(run_len)?;
+        run_lens.push(run_len as usize);
+    }
+    if total != nb_cells as u64 {
+        return None;
+    }
+    let mut cells = Vec::with_capacity(nb_cells);
+    let states = [Cell::Dead, Cell::Alive].iter().cycle();
+    for (&run_len, &state) in run_lens.iter().zip(states) {
+        cells.resize(cells.len() + run_len, state);
+    }
+    Some(cells)
+}
+
+/// Write a number in LEB128.
+fn write_leb128(data: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        data.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+/// Read a number in LEB128 from the beginning of the data, and remove it.
+/// Returns `None` if it is truncated or does not fit in 64 bits.
+fn read_leb128(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0;
+    for (idx, &byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7F) << (7 * idx);
+        if byte & 0x80 == 0 {
+            *data = &data[idx + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
@@ -0,0 +1,272 @@
+//! Rendering of a Universe, or of a part of it, as an SVG image, which can be
+//! scaled to any size such as for printing.
+
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+use crate::{Cell, MargolusRule, Universe};
+
+/// Draws the cells of a Universe as an SVG image.
+///
+/// Each cell is a square of `cell_size` units of side, the cell at a given
+/// row and column having its top-left corner at
+/// `(column * cell_size, row * cell_size)`. Live cells are drawn as few
+/// rectangles as possible, each covering a run of cells repeated over
+/// consecutive rows, on a background of the colour of the dead cells.
+/// Grid lines are one unit wide, centred on the edges of the cells.
+///
+/// By default, the whole Universe is drawn with cells of 10 units of side,
+/// live cells in black, dead ones in white and no grid lines, and the image
+/// describes the generation and rule it shows.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct SvgRenderer {
+    /// Number of units of the side of a cell.
+    cell_size: u32,
+
+    /// If `true`, lines are drawn between cells.
+    grid_lines: bool,
+
+    /// If `true`, the image describes the generation, rule and region shown.
+    metadata: bool,
+
+    /// Colours of the live cells, dead cells and grid lines, as RGBA bytes.
+    alive_color: [u8; 4],
+    dead_color: [u8; 4],
+    grid_color: [u8; 4],
+
+    /// First row, first column, height and width of the part of the Universe
+    /// to draw, if not the whole of it.
+    crop: Option<(u32, u32, u32, u32)>,
+}
+
+#[wasm_bindgen]
+impl SvgRenderer {
+    /// Create a renderer drawing whole Universes with cells of 10 units of
+    /// side, in black on white, without grid lines and with metadata.
+    pub fn new() -> SvgRenderer {
+        SvgRenderer {
+            cell_size: 10,
+            grid_lines: false,
+            metadata: true,
+            alive_color: [0x00, 0x00, 0x00, 0xFF],
+            dead_color: [0xFF, 0xFF, 0xFF, 0xFF],
+            grid_color: [0xCC, 0xCC, 0xCC, 0xFF],
+            crop: None,
+        }
+    }
+
+    /// Set the number of units of the side of each cell, at least `1`.
+    pub fn set_cell_size(&mut self, cell_size: u32) {
+        self.cell_size = cell_size.max(1);
+    }
+
+    /// Enable or disable the drawing of lines between cells.
+    pub fn set_grid_lines(&mut self, enabled: bool) {
+        self.grid_lines = enabled;
+    }
+
+    /// Enable or disable the description of the generation, rule and region
+    /// shown, in a `desc` element and in `data-` attributes of the image.
+    pub fn set_metadata(&mut self, enabled: bool) {
+        self.metadata = enabled;
+    }
+
+    /// Set the colour of the live cells, as a `0xRRGGBBAA` number.
+    pub fn set_alive_color(&mut self, color: u32) {
+        self.alive_color = color.to_be_bytes();
+    }
+
+    /// Set the colour of the dead cells, as a `0xRRGGBBAA` number.
+    /// No background is drawn if it is fully transparent.
+    pub fn set_dead_color(&mut self, color: u32) {
+        self.dead_color = color.to_be_bytes();
+    }
+
+    /// Set the colour of the grid lines, as a `0xRRGGBBAA` number.
+    pub fn set_grid_color(&mut self, color: u32) {
+        self.grid_color = color.to_be_bytes();
+    }
+
+    /// Only draw the part of the Universe of `height` rows and `width`
+    /// columns beginning at the given row and column.
+    /// The parts of that region outside of the Universe are ignored.
+    pub fn set_crop(&mut self, row: u32, column: u32, height: u32, width: u32) {
+        self.crop = Some((row, column, height, width));
+    }
+
+    /// Draw the whole Universe again, after a call to `set_crop`.
+    pub fn clear_crop(&mut self) {
+        self.crop = None;
+    }
+
+    /// Draw the current generation of the given Universe.
+    pub fn render(&self, universe: &Universe) -> String {
+        let (row, column, height, width) = self.crop
+            .unwrap_or((0, 0, universe.height, universe.width));
+        let first_row = row.min(universe.height);
+        let first_col = column.min(universe.width);
+        let end_row = row.saturating_add(height).min(universe.height);
+        let end_col = column.saturating_add(width).min(universe.width);
+        let cell_size = u64::from(self.cell_size);
+        let image_width = u64::from(end_col - first_col) * cell_size;
+        let image_height = u64::from(end_row - first_row) * cell_size;
+
+        let mut svg = String::new();
+        write!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" \
+                     width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\"",
+               image_width, image_height).unwrap();
+        if self.metadata {
+            let rule = describe_rule(universe);
+            writeln!(svg, " data-generation=\"{}\" data-rule=\"{}\" \
+                           data-region=\"{} {} {} {}\">",
+                   universe.generation, rule,
+                   first_row, first_col, end_row - first_row, end_col - first_col).unwrap();
+            write!(svg, "<desc>Universe of {}x{} cells following the rule {}, \
+                         at generation {}",
+                   universe.height, universe.width, rule, universe.generation).unwrap();
+            if self.crop.is_some() && first_row < end_row && first_col < end_col {
+                write!(svg, ", rows {} to {} and columns {} to {}",
+                       first_row, end_row - 1, first_col, end_col - 1).unwrap();
+            }
+            svg.push_str("</desc>\n");
+        } else {
+            svg.push_str(">\n");
+        }
+
+        if self.dead_color[3] != 0 {
+            writeln!(svg, "<rect width=\"{}\" height=\"{}\"{}/>",
+                     image_width, image_height, fill(self.dead_color)).unwrap();
+        }
+
+        // Runs of live cells of the previous row, as their first and last
+        // columns plus one, with the first of the consecutive rows where they
+        // are found. A run is drawn once the next row does not repeat it.
+        let mut open_runs: Vec<(u32, u32, u32)> = Vec::new();
+        let mut rectangles = String::new();
+        for row in first_row..=end_row {
+            let runs = if row < end_row {
+                live_runs(universe, row, first_col, end_col)
+            } else {
+                Vec::new()
+            };
+            let mut next_runs = Vec::with_capacity(runs.len());
+            for (start, end) in runs {
+                let top = open_runs.iter()
+                    .find(|&&(open_start, open_end, _)| (open_start, open_end) == (start, end))
+                    .map_or(row, |&(_, _, top)| top);
+                next_runs.push((start, end, top));
+            }
+            for &(start, end, top) in &open_runs {
+                if !next_runs.contains(&(start, end, top)) {
+                    writeln!(rectangles,
+                             "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                             u64::from(start - first_col) * cell_size,
+                             u64::from(top - first_row) * cell_size,
+                             u64::from(end - start) * cell_size,
+                             u64::from(row - top) * cell_size).unwrap();
+                }
+            }
+            open_runs = next_runs;
+        }
+        if !rectangles.is_empty() {
+            write!(svg, "<g{}>\n{}</g>\n", fill(self.alive_color), rectangles).unwrap();
+        }
+
+        if self.grid_lines && image_width > 0 && image_height > 0 {
+            let mut path = String::new();
+            for y in (0..=image_height).step_by(self.cell_size as usize) {
+                write!(path, "M0 {}H{}", y, image_width).unwrap();
+            }
+            for x in (0..=image_width).step_by(self.cell_size as usize) {
+                write!(path, "M{} 0V{}", x, image_height).unwrap();
+            }
+            let [red, green, blue, alpha] = self.grid_color;
+            write!(svg, "<path d=\"{}\" fill=\"none\" stroke=\"#{:02x}{:02x}{:02x}\"",
+                   path, red, green, blue).unwrap();
+            if alpha != 0xFF {
+                write!(svg, " stroke-opacity=\"{}\"", opacity(alpha)).unwrap();
+            }
+            svg.push_str(" stroke-width=\"1\"/>\n");
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl Default for SvgRenderer {
+    fn default() -> SvgRenderer {
+        SvgRenderer::new()
+    }
+}
+
+/// Returns the runs of live cells of the given row between `first_col` and
+/// `end_col`, as their first and last columns plus one, from left to right.
+fn live_runs(universe: &Universe, row: u32, first_col: u32, end_col: u32) -> Vec<(u32, u32)> {
+    let start = universe.get_index(row, first_col);
+    let cells = &universe.current_cells[start..start + (end_col - first_col) as usize];
+    let mut runs = Vec::new();
+    let mut run_start = None;
+    for (col, &cell) in (first_col..).zip(cells.iter().chain(&[Cell::Dead])) {
+        match (cell, run_start) {
+            (Cell::Alive, None) => run_start = Some(col),
+            (Cell::Dead, Some(start)) => {
+                runs.push((start, col));
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// Returns the attributes filling a shape with the given RGBA colour.
+fn fill(color: [u8; 4]) -> String {
+    let [red, green, blue, alpha] = color;
+    let mut attributes = format!(" fill=\"#{:02x}{:02x}{:02x}\"", red, green, blue);
+    if alpha != 0xFF {
+        write!(attributes, " fill-opacity=\"{}\"", opacity(alpha)).unwrap();
+    }
+    attributes
+}
+
+/// Returns an alpha value as an opacity from `0` to `1`, rounded to three
+/// decimals.
+fn opacity(alpha: u8) -> f64 {
+    (f64::from(alpha) / 255. * 1000.).round() / 1000.
+}
+
+/// Returns the name of the rule followed by the Universe, such as `B3/S23`
+/// or `Critters`, with its probabilities if it is stochastic.
+fn describe_rule(universe: &Universe) -> String {
+    let mut description = match universe.margolus_rule {
+        Some(rule) => {
+            let presets = [
+                (MargolusRule::critters(), "Critters"),
+                (MargolusRule::billiard_ball_machine(), "Billiard Ball Machine"),
+                (MargolusRule::tron(), "Tron"),
+            ];
+            match presets.iter().find(|(preset, _)| *preset == rule) {
+                Some((_, name)) => name.to_string(),
+                None => {
+                    let table: Vec<String> = rule.table().iter().map(u8::to_string).collect();
+                    format!("Margolus {}", table.join(","))
+                }
+            }
+        }
+        None => String::from("B3/S23"),
+    };
+    let mut probabilities = Vec::new();
+    if universe.margolus_rule.is_none() && universe.birth_probability < 1. {
+        probabilities.push(format!("birth probability {}", universe.birth_probability));
+    }
+    if universe.margolus_rule.is_none() && universe.survival_probability < 1. {
+        probabilities.push(format!("survival probability {}", universe.survival_probability));
+    }
+    if universe.noise_rate > 0. {
+        probabilities.push(format!("noise rate {}", universe.noise_rate));
+    }
+    if !probabilities.is_empty() {
+        write!(description, " ({})", probabilities.join(", ")).unwrap();
+    }
+    description
+}
@@ -0,0 +1,228 @@
+//! Rendering of a Universe as text for ANSI terminals, where cells can be
+//! drawn with half blocks to fit two rows in each line of characters.
+
+use crate::{Cell, Universe};
+
+/// Colour of the live cells in the 256-colour palette of terminals.
+const ALIVE_COLOR: u8 = 231;
+
+/// Colours of the cell under the cursor, whether it is alive or dead.
+const CURSOR_ALIVE_COLOR: u8 = 201;
+const CURSOR_DEAD_COLOR: u8 = 53;
+
+/// Maximum age and colour of the live cells by age: a cell is drawn with the
+/// colour of the first entry whose maximum age it does not exceed.
+const AGE_COLORS: [(u16, u8); 6] = [
+    (1, 231),
+    (3, 226),
+    (10, 214),
+    (50, 202),
+    (200, 160),
+    (u16::MAX, 88),
+];
+
+/// How cells are coloured in a terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    /// No colour is used, live cells being drawn as blocks.
+    Monochrome,
+    /// Live cells are drawn in the same colour.
+    State,
+    /// Live cells are coloured from white to red as they get older.
+    /// Ages are only known if their tracking is enabled (see
+    /// `Universe::set_age_tracking`), cells being otherwise drawn as with
+    /// `ColorMode::State`.
+    Age,
+}
+
+/// Draws a rectangle of the cells of a Universe as lines of text with ANSI
+/// escape sequences, dead cells being left to the terminal's background.
+#[derive(Clone, Debug)]
+pub struct TerminalRenderer {
+    color_mode: ColorMode,
+
+    /// If `true`, each character shows two rows of cells through half
+    /// blocks, which makes cells look about square.
+    half_blocks: bool,
+
+    /// Row and column of the cell highlighted as a cursor, if any.
+    cursor: Option<(u32, u32)>,
+}
+
+impl TerminalRenderer {
+    /// Create a renderer drawing live cells in a single colour, with half
+    /// blocks and no cursor.
+    pub fn new() -> TerminalRenderer {
+        TerminalRenderer {
+            color_mode: ColorMode::State,
+            half_blocks: true,
+            cursor: None,
+        }
+    }
+
+    /// Returns how cells are coloured.
+    pub fn color_mode(&self) -> ColorMode {
+        self.color_mode
+    }
+
+    /// Set how cells are coloured.
+    pub fn set_color_mode(&mut self, color_mode: ColorMode) {
+        self.color_mode = color_mode;
+    }
+
+    /// Returns `true` if two rows of cells are drawn per line.
+    pub fn half_blocks(&self) -> bool {
+        self.half_blocks
+    }
+
+    /// Enable or disable the drawing of two rows of cells per line.
+    pub fn set_half_blocks(&mut self, enabled: bool) {
+        self.half_blocks = enabled;
+    }
+
+    /// Highlight the cell at the given row and column, or none.
+    pub fn set_cursor(&mut self, cursor: Option<(u32, u32)>) {
+        self.cursor = cursor;
+    }
+
+    /// Returns the number of rows of cells shown by each line.
+    pub fn rows_per_line(&self) -> u32 {
+        if self.half_blocks { 2 } else { 1 }
+    }
+
+    /// Draw the cells of the Universe from `first_row` and `first_column`
+    /// into `nb_lines` lines of `nb_columns` characters, one per column of
+    /// cells. Parts of those lines outside of the Universe are left blank.
+    ///
+    /// Lines do not end with a line break, and reset their style at their
+    /// end so they can be printed anywhere.
+    pub fn render(
+        &self,
+        universe: &Universe,
+        first_row: u32,
+        first_column: u32,
+        nb_lines: u32,
+        nb_columns: u32
+    ) -> Vec<String> {
+        let rows_per_line = self.rows_per_line();
+        (0..nb_lines)
+            .map(|line_idx| {
+                let row = u64::from(first_row) + u64::from(line_idx * rows_per_line);
+                let mut line = String::new();
+                let mut style = Style::default();
+                for col_idx in 0..nb_columns {
+                    let column = u64::from(first_column) + u64::from(col_idx);
+                    let top = self.cell_color(universe, row, column);
+                    let (glyph, mut new_style) = if self.half_blocks {
+                        let bottom = self.cell_color(universe, row + 1, column);
+                        half_block(top, bottom)
+                    } else {
+                        full_block(top)
+                    };
+                    if self.color_mode == ColorMode::Monochrome {
+                        let has_cursor = self.cursor.is_some_and(|(cursor_row, cursor_col)| {
+                            u64::from(cursor_col) == column &&
+                                (row..row + u64::from(rows_per_line))
+                                    .contains(&u64::from(cursor_row))
+                        });
+                        new_style = Style { inverse: has_cursor, ..Style::default() };
+                    }
+                    if new_style != style {
+                        new_style.write(&mut line);
+                        style = new_style;
+                    }
+                    line.push(glyph);
+                }
+                if style != Style::default() {
+                    Style::default().write(&mut line);
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Returns the colour of the cell at the given row and column, or `None`
+    /// if it is dead or outside of the Universe.
+    fn cell_color(&self, universe: &Universe, row: u64, column: u64) -> Option<u8> {
+        if row >= u64::from(universe.height) || column >= u64::from(universe.width) {
+            return None;
+        }
+        let (row, column) = (row as u32, column as u32);
+        let cell = universe.current_cells[universe.get_index(row, column)];
+        if self.color_mode != ColorMode::Monochrome && self.cursor == Some((row, column)) {
+            return match cell {
+                Cell::Alive => Some(CURSOR_ALIVE_COLOR),
+                Cell::Dead => Some(CURSOR_DEAD_COLOR),
+            };
+        }
+        if cell == Cell::Dead {
+            return None;
+        }
+        let ages = universe.ages();
+        if self.color_mode == ColorMode::Age && !ages.is_empty() {
+            let age = ages[(row * universe.width + column) as usize];
+            AGE_COLORS.iter()
+                .find(|&&(max_age, _)| age <= max_age)
+                .map(|&(_, color)| color)
+        } else {
+            Some(ALIVE_COLOR)
+        }
+    }
+}
+
+impl Default for TerminalRenderer {
+    fn default() -> TerminalRenderer {
+        TerminalRenderer::new()
+    }
+}
+
+/// Attributes of the characters drawn in a terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Style {
+    foreground: Option<u8>,
+    background: Option<u8>,
+    inverse: bool,
+}
+
+impl Style {
+    /// Write the escape sequence switching to that style, whatever the
+    /// previous one was.
+    fn write(&self, line: &mut String) {
+        line.push_str("\x1b[0");
+        if let Some(color) = self.foreground {
+            line.push_str(&format!(";38;5;{}", color));
+        }
+        if let Some(color) = self.background {
+            line.push_str(&format!(";48;5;{}", color));
+        }
+        if self.inverse {
+            line.push_str(";7");
+        }
+        line.push('m');
+    }
+}
+
+/// Returns the character and style showing two cells on top of each other,
+/// from their colours.
+fn half_block(top: Option<u8>, bottom: Option<u8>) -> (char, Style) {
+    let style = |foreground, background| Style {
+        foreground: Some(foreground),
+        background,
+        inverse: false,
+    };
+    match (top, bottom) {
+        (None, None) => (' ', Style::default()),
+        (Some(top), None) => ('▀', style(top, None)),
+        (None, Some(bottom)) => ('▄', style(bottom, None)),
+        (Some(top), Some(bottom)) if top == bottom => ('█', style(top, None)),
+        (Some(top), Some(bottom)) => ('▀', style(top, Some(bottom))),
+    }
+}
+
+/// Returns the character and style showing a single cell, from its colour.
+fn full_block(color: Option<u8>) -> (char, Style) {
+    match color {
+        None => (' ', Style::default()),
+        Some(color) => ('█', Style { foreground: Some(color), ..Style::default() }),
+    }
+}
@@ -0,0 +1,180 @@
+//! Rendering of a Universe, or of a part of it, as plain text, such as to show
+//! it in logs or in the failure of a test.
+
+use wasm_bindgen::prelude::*;
+use crate::{Cell, Universe};
+
+/// First Braille pattern, whose dots are all lowered.
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// Bit of each dot of a Braille pattern, by row then column of the 2x4 cells
+/// it shows.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80],
+];
+
+/// How cells are written as characters.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextMode {
+    /// One character per cell, chosen depending on its state.
+    Glyphs,
+    /// One Braille pattern per block of 2 columns and 4 rows of cells, whose
+    /// raised dots are the live cells.
+    Braille,
+}
+
+/// Writes the cells of a Universe as lines of text, each ending with a line
+/// break.
+///
+/// By default, the whole Universe is written with `◻` for dead cells and `◼`
+/// for live ones, as done by its `Display` implementation.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct TextRenderer {
+    mode: TextMode,
+
+    /// Characters of the dead and live cells, in `TextMode::Glyphs`.
+    dead_char: char,
+    alive_char: char,
+
+    /// First row, first column, height and width of the part of the Universe
+    /// to write, if not the whole of it.
+    window: Option<(u32, u32, u32, u32)>,
+}
+
+#[wasm_bindgen]
+impl TextRenderer {
+    /// Create a renderer writing the whole Universe with `◻` for dead cells
+    /// and `◼` for live ones.
+    pub fn new() -> TextRenderer {
+        TextRenderer {
+            mode: TextMode::Glyphs,
+            dead_char: '◻',
+            alive_char: '◼',
+            window: None,
+        }
+    }
+
+    /// Create a renderer writing the whole Universe in the style of the
+    /// plaintext pattern format, with `.` for dead cells and `O` for live
+    /// ones.
+    pub fn plaintext() -> TextRenderer {
+        TextRenderer {
+            dead_char: '.',
+            alive_char: 'O',
+            ..TextRenderer::new()
+        }
+    }
+
+    /// Set how cells are written as characters.
+    pub fn set_mode(&mut self, mode: TextMode) {
+        self.mode = mode;
+    }
+
+    /// Set the character of the dead cells, when writing one per cell.
+    pub fn set_dead_char(&mut self, dead_char: char) {
+        self.dead_char = dead_char;
+    }
+
+    /// Set the character of the live cells, when writing one per cell.
+    pub fn set_alive_char(&mut self, alive_char: char) {
+        self.alive_char = alive_char;
+    }
+
+    /// Only write the part of the Universe of `height` rows and `width`
+    /// columns beginning at the given row and column.
+    /// The parts of that window outside of the Universe are ignored.
+    pub fn set_window(&mut self, row: u32, column: u32, height: u32, width: u32) {
+        self.window = Some((row, column, height, width));
+    }
+
+    /// Write the whole Universe again, after a call to `set_window`.
+    pub fn clear_window(&mut self) {
+        self.window = None;
+    }
+
+    /// Write the current generation of the given Universe.
+    pub fn render(&self, universe: &Universe) -> String {
+        let (row, column, height, width) = self.window
+            .unwrap_or((0, 0, universe.height, universe.width));
+        let first_row = row.min(universe.height);
+        let first_col = column.min(universe.width);
+        let end_row = row.saturating_add(height).min(universe.height);
+        let end_col = column.saturating_add(width).min(universe.width);
+        if first_row >= end_row || first_col >= end_col {
+            return String::new();
+        }
+        let window = Window { first_row, first_col, end_row, end_col };
+        match self.mode {
+            TextMode::Glyphs => self.render_glyphs(universe, &window),
+            TextMode::Braille => render_braille(universe, &window),
+        }
+    }
+}
+
+impl TextRenderer {
+    fn render_glyphs(&self, universe: &Universe, window: &Window) -> String {
+        let mut text = String::new();
+        for row in window.first_row..window.end_row {
+            let start = universe.get_index(row, window.first_col);
+            let cells = &universe.current_cells[start..start + window.width()];
+            text.extend(cells.iter().map(|&cell| match cell {
+                Cell::Dead => self.dead_char,
+                Cell::Alive => self.alive_char,
+            }));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl Default for TextRenderer {
+    fn default() -> TextRenderer {
+        TextRenderer::new()
+    }
+}
+
+/// Part of a Universe to write, within its boundaries.
+struct Window {
+    first_row: u32,
+    first_col: u32,
+    end_row: u32,
+    end_col: u32,
+}
+
+impl Window {
+    fn width(&self) -> usize {
+        (self.end_col - self.first_col) as usize
+    }
+}
+
+/// Write the cells of the window as Braille patterns, cells of the blocks
+/// crossing its edges being considered dead if they are out of it.
+fn render_braille(universe: &Universe, window: &Window) -> String {
+    let mut text = String::new();
+    for block_row in (window.first_row..window.end_row).step_by(4) {
+        for block_col in (window.first_col..window.end_col).step_by(2) {
+            let mut pattern = BRAILLE_BLANK;
+            for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                let row = block_row + dy as u32;
+                if row >= window.end_row {
+                    break;
+                }
+                for (dx, dot) in dots.iter().enumerate() {
+                    let col = block_col + dx as u32;
+                    if col < window.end_col &&
+                        universe.current_cells[universe.get_index(row, col)] == Cell::Alive {
+                        pattern |= dot;
+                    }
+                }
+            }
+            text.push(char::from_u32(pattern).unwrap());
+        }
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,157 @@
+/// Number of rows and columns of cells in a tile.
+pub(crate) const TILE_SIZE: u32 = 32;
+
+/// Keep track of which parts of a Universe changed during the last
+/// generation, so the next one only has to be computed around them.
+///
+/// The Universe is divided into square tiles of `TILE_SIZE` cells of side
+/// (those on the last row and column might be smaller).
+/// A tile needs to be computed again only if it, or one of the eight tiles
+/// around it, changed during the last generation: otherwise its cells have
+/// the same neighbours than before and thus cannot change.
+#[derive(Clone, Debug)]
+pub(crate) struct ActiveTiles {
+    /// Number of rows of tiles.
+    tile_rows: u32,
+
+    /// Number of columns of tiles.
+    tile_columns: u32,
+
+    /// For each tile, in row then column order, `true` if one of its cells
+    /// changed during the last generation.
+    changed: Vec<bool>,
+
+    /// For each tile, `true` if it needs to be computed during the next
+    /// generation.
+    dirty: Vec<bool>,
+
+    /// If `false`, `changed` cannot be relied on and every tile should be
+    /// computed.
+    valid: bool,
+
+    /// Number of tiles computed during the last tick.
+    computed: u32,
+
+    /// Number of tiles skipped during the last tick.
+    skipped: u32,
+}
+
+impl ActiveTiles {
+    /// Create a new `ActiveTiles` for a Universe of the given dimensions.
+    /// Every tile will be computed during the first generation.
+    pub fn new(height: u32, width: u32) -> ActiveTiles {
+        let tile_rows = height.div_ceil(TILE_SIZE);
+        let tile_columns = width.div_ceil(TILE_SIZE);
+        let nb_tiles = (tile_rows * tile_columns) as usize;
+        ActiveTiles {
+            tile_rows,
+            tile_columns,
+            changed: vec![true; nb_tiles],
+            dirty: vec![true; nb_tiles],
+            valid: false,
+            computed: 0,
+            skipped: 0,
+        }
+    }
+
+    /// Returns the number of tiles computed during the last tick.
+    pub fn computed(&self) -> u32 {
+        self.computed
+    }
+
+    /// Returns the number of tiles skipped during the last tick.
+    pub fn skipped(&self) -> u32 {
+        self.skipped
+    }
+
+    /// Returns the number of tiles in a row of the Universe.
+    pub fn tile_columns(&self) -> u32 {
+        self.tile_columns
+    }
+
+    /// Consider that every tile changed, for example because a large part of
+    /// the Universe was updated outside of a tick, or because the last
+    /// generations did not follow the Game of Life's deterministic rules so
+    /// that stable tiles may only have been so by chance.
+    pub fn invalidate(&mut self) {
+        self.valid = false;
+    }
+
+    /// Indicate that the cell at the given row and column was updated outside
+    /// of a tick.
+    pub fn mark_cell(&mut self, row: u32, column: u32) {
+        let tile = (row / TILE_SIZE) * self.tile_columns + column / TILE_SIZE;
+        self.changed[tile as usize] = true;
+    }
+
+    /// Reset the tiles which changed from the indices of the cells which did,
+    /// after a generation for which every tile was computed.
+    pub fn set_changes_from_cells(&mut self, changed_cells: &[u32], width: u32) {
+        for changed in self.changed.iter_mut() {
+            *changed = false;
+        }
+        for &idx in changed_cells {
+            self.mark_cell(idx / width, idx % width);
+        }
+        self.valid = true;
+        self.computed = self.tile_rows * self.tile_columns;
+        self.skipped = 0;
+    }
+
+    /// Compute which tiles need to be computed for the next generation and
+    /// reset the list of tiles which changed, which should then be filled
+    /// through `set_changed` while computing it.
+    pub fn start_generation(&mut self) {
+        self.computed = 0;
+        self.skipped = 0;
+        for tile_row in 0..self.tile_rows {
+            for tile_col in 0..self.tile_columns {
+                let tile = (tile_row * self.tile_columns + tile_col) as usize;
+                self.dirty[tile] = !self.valid ||
+                    self.has_changed_around(tile_row, tile_col);
+            }
+        }
+        for changed in self.changed.iter_mut() {
+            *changed = false;
+        }
+        self.valid = true;
+    }
+
+    /// Returns `true` if the given tile has to be computed during the current
+    /// generation.
+    pub fn is_dirty(&self, tile: usize) -> bool {
+        self.dirty[tile]
+    }
+
+    /// Indicate that a cell in the given tile changed during the current
+    /// generation.
+    pub fn set_changed(&mut self, tile: usize) {
+        self.changed[tile] = true;
+    }
+
+    /// Update the tick counters once a generation has been computed.
+    pub fn end_generation(&mut self) {
+        self.computed = self.dirty.iter().filter(|&&dirty| dirty).count() as u32;
+        self.skipped = self.dirty.len() as u32 - self.computed;
+    }
+
+    /// Returns `true` if the given tile or one of the eight tiles around it,
+    /// wrapping around the edges, changed during the last generation.
+    fn has_changed_around(&self, tile_row: u32, tile_col: u32) -> bool {
+        let rows = [
+            if tile_row == 0 { self.tile_rows - 1 } else { tile_row - 1 },
+            tile_row,
+            if tile_row + 1 == self.tile_rows { 0 } else { tile_row + 1 },
+        ];
+        let columns = [
+            if tile_col == 0 { self.tile_columns - 1 } else { tile_col - 1 },
+            tile_col,
+            if tile_col + 1 == self.tile_columns { 0 } else { tile_col + 1 },
+        ];
+        rows.iter().any(|&row| {
+            columns.iter().any(|&col| {
+                self.changed[(row * self.tile_columns + col) as usize]
+            })
+        })
+    }
+}
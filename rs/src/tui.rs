@@ -0,0 +1,199 @@
+//! Interactive player running a Universe in a terminal, the native
+//! counterpart of the web page's play/pause, next frame and speed controls.
+//!
+//! Keys:
+//!
+//!   - `space`: play or pause
+//!   - `n`: compute the next generation and pause
+//!   - `+` and `-`: increase or decrease the speed
+//!   - arrows or `h`, `j`, `k` and `l`: move the cursor
+//!   - `enter` or `x`: toggle the cell under the cursor
+//!   - `c`: clear the Universe
+//!   - `b`: switch between one and two rows of cells per line
+//!   - `m`: switch between the colour modes
+//!   - `q` or `escape`: quit
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use crossterm::{cursor, event, execute, queue, style, terminal};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crate::{ColorMode, TerminalRenderer, Universe};
+
+/// Speeds which can be chosen, in generations per second, `None` meaning as
+/// fast as possible.
+const SPEEDS: [Option<u32>; 10] = [
+    Some(1), Some(2), Some(5), Some(10), Some(15), Some(20), Some(30), Some(60),
+    Some(100), None,
+];
+
+/// Index in `SPEEDS` of the speed when the player starts.
+const DEFAULT_SPEED: usize = 3;
+
+/// Time waited for a key when paused, before drawing again in case the
+/// terminal was resized.
+const IDLE_REFRESH: Duration = Duration::from_millis(250);
+
+/// State of the player between two keys.
+struct Player<'a> {
+    universe: &'a mut Universe,
+    renderer: TerminalRenderer,
+    playing: bool,
+
+    /// Index of the current speed in `SPEEDS`.
+    speed: usize,
+
+    /// Row and column of the cell under the cursor.
+    cursor: (u32, u32),
+
+    /// Row and column of the cell at the top-left corner of the terminal.
+    origin: (u32, u32),
+}
+
+/// Run the Universe in the terminal until the user quits, starting paused.
+///
+/// The terminal is put in raw mode and switched to its alternate screen
+/// meanwhile, and restored before returning, even on errors.
+pub fn play(universe: &mut Universe) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    let result = execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)
+        .and_then(|_| Player::new(universe).run(&mut stdout));
+    let restored = execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)
+        .and_then(|_| terminal::disable_raw_mode());
+    result.and(restored)
+}
+
+impl<'a> Player<'a> {
+    fn new(universe: &'a mut Universe) -> Player<'a> {
+        universe.set_age_tracking(true);
+        let cursor = (universe.height() / 2, universe.width() / 2);
+        let mut renderer = TerminalRenderer::new();
+        renderer.set_color_mode(ColorMode::Age);
+        Player {
+            universe,
+            renderer,
+            playing: false,
+            speed: DEFAULT_SPEED,
+            cursor,
+            origin: (0, 0),
+        }
+    }
+
+    fn run(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let mut next_tick = Instant::now();
+        loop {
+            self.draw(out)?;
+            let timeout = if self.playing {
+                next_tick.saturating_duration_since(Instant::now())
+            } else {
+                IDLE_REFRESH
+            };
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if !self.handle_key(key) {
+                        return Ok(());
+                    }
+                }
+            }
+            if self.playing && Instant::now() >= next_tick {
+                self.universe.tick();
+                next_tick = match SPEEDS[self.speed] {
+                    Some(speed) => Instant::now() + Duration::from_secs(1) / speed,
+                    None => Instant::now(),
+                };
+            }
+        }
+    }
+
+    /// Update the player according to the key pressed.
+    /// Returns `false` if the user wants to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return true;
+        }
+        let (row, column) = self.cursor;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') => self.playing = !self.playing,
+            KeyCode::Char('n') => {
+                self.playing = false;
+                self.universe.tick();
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') =>
+                self.speed = (self.speed + 1).min(SPEEDS.len() - 1),
+            KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Up | KeyCode::Char('k') => self.cursor.0 = row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') =>
+                self.cursor.0 = (row + 1).min(self.universe.height() - 1),
+            KeyCode::Left | KeyCode::Char('h') => self.cursor.1 = column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') =>
+                self.cursor.1 = (column + 1).min(self.universe.width() - 1),
+            KeyCode::Enter | KeyCode::Char('x') => self.universe.toggle_cell(row, column),
+            KeyCode::Char('c') => self.universe.clear(),
+            KeyCode::Char('b') => {
+                let half_blocks = self.renderer.half_blocks();
+                self.renderer.set_half_blocks(!half_blocks);
+            }
+            KeyCode::Char('m') => {
+                let color_mode = match self.renderer.color_mode() {
+                    ColorMode::Age => ColorMode::State,
+                    ColorMode::State => ColorMode::Monochrome,
+                    ColorMode::Monochrome => ColorMode::Age,
+                };
+                self.renderer.set_color_mode(color_mode);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Draw the part of the Universe around the cursor, followed by a status
+    /// line.
+    fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let (nb_columns, nb_lines) = terminal::size()?;
+        let nb_lines = u32::from(nb_lines.saturating_sub(1)).max(1);
+        let nb_columns = u32::from(nb_columns).max(1);
+        let nb_rows = nb_lines * self.renderer.rows_per_line();
+        self.origin.0 = scroll_to(self.origin.0, self.cursor.0, nb_rows);
+        self.origin.1 = scroll_to(self.origin.1, self.cursor.1, nb_columns);
+
+        self.renderer.set_cursor(Some(self.cursor));
+        let lines = self.renderer.render(self.universe, self.origin.0, self.origin.1,
+                                         nb_lines, nb_columns);
+        for (line_idx, line) in lines.iter().enumerate() {
+            queue!(out,
+                   cursor::MoveTo(0, line_idx as u16),
+                   style::Print(line),
+                   terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+
+        let speed = match SPEEDS[self.speed] {
+            Some(speed) => format!("{}/s", speed),
+            None => "max".to_owned(),
+        };
+        let status = format!(
+            "{} | generation {} | population {} | speed {} | cell {},{} | \
+             space: play/pause  n: next  +/-: speed  x: toggle  q: quit",
+            if self.playing { "playing" } else { "paused" },
+            self.universe.generation(), self.universe.population(), speed,
+            self.cursor.0, self.cursor.1);
+        let status: String = status.chars().take(nb_columns as usize).collect();
+        queue!(out,
+               cursor::MoveTo(0, nb_lines as u16),
+               style::Print(status),
+               terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        out.flush()
+    }
+}
+
+/// Returns the first row or column to show so that `cursor` is visible among
+/// the `nb_shown` following `origin`, moving as little as possible.
+fn scroll_to(origin: u32, cursor: u32, nb_shown: u32) -> u32 {
+    if cursor < origin {
+        cursor
+    } else if cursor >= origin + nb_shown {
+        cursor + 1 - nb_shown
+    } else {
+        origin
+    }
+}
@@ -0,0 +1,307 @@
+//! Rendering of the visible part of a Universe into a buffer of pixels the
+//! size of the screen, allowing to zoom and pan over Universes larger than
+//! it.
+
+use wasm_bindgen::prelude::*;
+use crate::{Cell, Universe};
+
+/// Smallest zoom level: 256 cells of side are then shown in a single pixel.
+const MIN_ZOOM: i32 = -8;
+
+/// Largest zoom level: cells are then 64 pixels of side.
+const MAX_ZOOM: i32 = 6;
+
+/// Smallest number of pixels of the side of a cell for which grid lines are
+/// drawn.
+const MIN_GRID_CELL_SIZE: f64 = 4.;
+
+/// Part of a Universe shown on a screen of a given size, in pixels.
+///
+/// The zoom level indicates the size of a cell: at level `n`, a cell is
+/// `2^n` pixels of side. Negative levels zoom out, a pixel then covering
+/// `2^-n` cells of side: its colour reflects the proportion of live cells
+/// among them, from the dead colour to the live one.
+///
+/// The image is stored in the same layout as a `Framebuffer`: four bytes per
+/// pixel (red, green, blue and alpha), in row then column order, without
+/// padding.
+#[wasm_bindgen]
+pub struct Viewport {
+    /// Width of the screen, in pixels.
+    screen_width: u32,
+
+    /// Height of the screen, in pixels.
+    screen_height: u32,
+
+    /// Column, possibly fractional, shown at the left of the screen.
+    origin_column: f64,
+
+    /// Row, possibly fractional, shown at the top of the screen.
+    origin_row: f64,
+
+    /// Zoom level, from `MIN_ZOOM` to `MAX_ZOOM`.
+    zoom: i32,
+
+    /// If `true`, lines are drawn between cells when they are large enough.
+    grid_lines: bool,
+
+    /// Colours of the live cells, dead cells, grid lines and of the area
+    /// outside the Universe, as RGBA bytes.
+    alive_color: [u8; 4],
+    dead_color: [u8; 4],
+    grid_color: [u8; 4],
+    background_color: [u8; 4],
+
+    /// RGBA bytes of every pixel, in row then column order.
+    pixels: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Viewport {
+    /// Create a viewport for a screen of the given size, in pixels, showing
+    /// cells of 8 pixels of side from the top-left corner of the Universe.
+    pub fn new(screen_width: u32, screen_height: u32) -> Viewport {
+        let mut viewport = Viewport {
+            screen_width: 0,
+            screen_height: 0,
+            origin_column: 0.,
+            origin_row: 0.,
+            zoom: 3,
+            grid_lines: true,
+            alive_color: [0x00, 0x00, 0x00, 0xFF],
+            dead_color: [0xFF, 0xFF, 0xFF, 0xFF],
+            grid_color: [0xCC, 0xCC, 0xCC, 0xFF],
+            background_color: [0xEE, 0xEE, 0xEE, 0xFF],
+            pixels: Vec::new(),
+        };
+        viewport.set_screen_size(screen_width, screen_height);
+        viewport
+    }
+
+    /// Change the size of the screen, in pixels, keeping the same cell at its
+    /// top-left corner.
+    pub fn set_screen_size(&mut self, screen_width: u32, screen_height: u32) {
+        self.screen_width = screen_width;
+        self.screen_height = screen_height;
+        let len = screen_width as usize * screen_height as usize * 4;
+        self.pixels.resize(len, 0);
+    }
+
+    /// Returns the width of the screen, in pixels.
+    pub fn width(&self) -> u32 {
+        self.screen_width
+    }
+
+    /// Returns the height of the screen, in pixels.
+    pub fn height(&self) -> u32 {
+        self.screen_height
+    }
+
+    /// Returns the current zoom level.
+    pub fn zoom(&self) -> i32 {
+        self.zoom
+    }
+
+    /// Set the zoom level, bounded from `-8` to `6`, keeping the same cell at
+    /// the center of the screen.
+    pub fn set_zoom(&mut self, zoom: i32) {
+        let (x, y) = (f64::from(self.screen_width) / 2., f64::from(self.screen_height) / 2.);
+        self.zoom_at(x, y, zoom.saturating_sub(self.zoom));
+    }
+
+    /// Change the zoom level by `delta` (positive to zoom in), keeping the
+    /// same cell under the given screen coordinates, such as those of the
+    /// mouse.
+    pub fn zoom_at(&mut self, x: f64, y: f64, delta: i32) {
+        let column = self.origin_column + x / self.cell_size();
+        let row = self.origin_row + y / self.cell_size();
+        self.zoom = self.zoom.saturating_add(delta).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.origin_column = column - x / self.cell_size();
+        self.origin_row = row - y / self.cell_size();
+    }
+
+    /// Move the viewport by the given number of pixels, such as those of the
+    /// mouse while dragging: the cells follow the same movement.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.origin_column -= dx / self.cell_size();
+        self.origin_row -= dy / self.cell_size();
+    }
+
+    /// Move the viewport so the center of the given Universe is at the center
+    /// of the screen.
+    pub fn center_on(&mut self, universe: &Universe) {
+        let cell_size = self.cell_size();
+        self.origin_column = f64::from(universe.width) / 2. -
+            f64::from(self.screen_width) / 2. / cell_size;
+        self.origin_row = f64::from(universe.height) / 2. -
+            f64::from(self.screen_height) / 2. / cell_size;
+    }
+
+    /// Enable or disable the lines of one pixel separating cells, which are
+    /// only drawn when cells are at least 4 pixels of side.
+    pub fn set_grid_lines(&mut self, enabled: bool) {
+        self.grid_lines = enabled;
+    }
+
+    /// Set the colour of the live cells, as a `0xRRGGBBAA` number.
+    pub fn set_alive_color(&mut self, color: u32) {
+        self.alive_color = color.to_be_bytes();
+    }
+
+    /// Set the colour of the dead cells, as a `0xRRGGBBAA` number.
+    pub fn set_dead_color(&mut self, color: u32) {
+        self.dead_color = color.to_be_bytes();
+    }
+
+    /// Set the colour of the grid lines, as a `0xRRGGBBAA` number.
+    pub fn set_grid_color(&mut self, color: u32) {
+        self.grid_color = color.to_be_bytes();
+    }
+
+    /// Set the colour of the area outside the Universe, as a `0xRRGGBBAA`
+    /// number.
+    pub fn set_background_color(&mut self, color: u32) {
+        self.background_color = color.to_be_bytes();
+    }
+
+    /// Returns the column of the Universe's cell at the given horizontal
+    /// screen coordinate, if there is one.
+    pub fn column_at(&self, universe: &Universe, x: f64) -> Option<u32> {
+        let column = (self.origin_column + x / self.cell_size()).floor();
+        if column >= 0. && column < f64::from(universe.width) {
+            Some(column as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the row of the Universe's cell at the given vertical screen
+    /// coordinate, if there is one.
+    pub fn row_at(&self, universe: &Universe, y: f64) -> Option<u32> {
+        let row = (self.origin_row + y / self.cell_size()).floor();
+        if row >= 0. && row < f64::from(universe.height) {
+            Some(row as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Get pointer to the first byte of the last rendered image.
+    /// From this pointer, you should expect `pixels_len()` bytes, see
+    /// `Viewport` for their layout.
+    pub fn pixels_ptr(&self) -> *const u8 {
+        self.pixels.as_ptr()
+    }
+
+    /// Returns the number of bytes of the image, which is
+    /// `width * height * 4`.
+    pub fn pixels_len(&self) -> u32 {
+        self.pixels.len() as u32
+    }
+
+    /// Draw the part of the given Universe visible on the screen.
+    pub fn render(&mut self, universe: &Universe) {
+        let columns = self.visible_spans(self.origin_column, self.screen_width, universe.width);
+        let rows = self.visible_spans(self.origin_row, self.screen_height, universe.height);
+        let row_len = self.screen_width as usize * 4;
+        for (line, row_span) in self.pixels.chunks_exact_mut(row_len).zip(rows.iter()) {
+            let pixels = line.chunks_exact_mut(4).zip(columns.iter());
+            for (pixel, column_span) in pixels {
+                let color = match (row_span, column_span) {
+                    (None, _) | (_, None) => self.background_color,
+                    (Some(row_span), Some(column_span)) => {
+                        if row_span.on_grid || column_span.on_grid {
+                            self.grid_color
+                        } else {
+                            let live = count_live_cells(universe, row_span, column_span);
+                            let total = (row_span.end - row_span.start) *
+                                (column_span.end - column_span.start);
+                            blend(self.dead_color, self.alive_color, live, total)
+                        }
+                    }
+                };
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+impl Viewport {
+    /// Returns the number of pixels of the side of a cell, which is less than
+    /// one when zoomed out.
+    fn cell_size(&self) -> f64 {
+        2f64.powi(self.zoom)
+    }
+
+    /// For each of the `nb_pixels` pixels of a screen's line beginning at the
+    /// `origin` column or row, returns the span of cells it shows among the
+    /// `nb_cells` of the Universe in that direction, if any.
+    fn visible_spans(&self, origin: f64, nb_pixels: u32, nb_cells: u32) -> Vec<Option<Span>> {
+        let cell_size = self.cell_size();
+        let draw_grid = self.grid_lines && cell_size >= MIN_GRID_CELL_SIZE;
+        let cell_at = |pixel: u32| (origin + f64::from(pixel) / cell_size).floor();
+        (0..nb_pixels)
+            .map(|pixel| {
+                let first = cell_at(pixel);
+                let last = if cell_size >= 1. {
+                    first + 1.
+                } else {
+                    cell_at(pixel + 1)
+                };
+                let start = first.max(0.);
+                let end = last.min(f64::from(nb_cells));
+                if start >= end {
+                    return None;
+                }
+                // The first line of a cell is the pixel where it begins, less
+                // than a pixel before the current one, which for the first
+                // pixel depends on the fractional part of `origin`.
+                let offset = (origin + f64::from(pixel) / cell_size - first) * cell_size;
+                Some(Span {
+                    start: start as u32,
+                    end: end as u32,
+                    on_grid: draw_grid && offset < 1.,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Range of rows or columns of cells shown by a pixel.
+struct Span {
+    /// First row or column shown.
+    start: u32,
+
+    /// Row or column following the last one shown.
+    end: u32,
+
+    /// `true` if the pixel is on the first line of a cell, where a grid line
+    /// is drawn.
+    on_grid: bool,
+}
+
+/// Returns the number of live cells in the given rows and columns of a
+/// Universe.
+fn count_live_cells(universe: &Universe, rows: &Span, columns: &Span) -> u32 {
+    (rows.start..rows.end)
+        .map(|row| {
+            let start = universe.get_index(row, columns.start);
+            let end = start + (columns.end - columns.start) as usize;
+            universe.current_cells[start..end]
+                .iter()
+                .filter(|&&cell| cell == Cell::Alive)
+                .count() as u32
+        })
+        .sum()
+}
+
+/// Returns the colour between `from` and `to` for a proportion of
+/// `part / total`.
+fn blend(from: [u8; 4], to: [u8; 4], part: u32, total: u32) -> [u8; 4] {
+    let mut color = [0; 4];
+    for (i, channel) in color.iter_mut().enumerate() {
+        let (from, to) = (i64::from(from[i]), i64::from(to[i]));
+        *channel = (from + (to - from) * i64::from(part) / i64::from(total)) as u8;
+    }
+    color
+}
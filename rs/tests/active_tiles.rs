@@ -0,0 +1,102 @@
+//! Tests for the tracking of the parts of a Universe which changed.
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use wasm_game_of_life::{Cell, MargolusPreset, Universe};
+use common::random_soup_in_corner;
+
+#[test]
+pub fn test_same_result_than_full_scan() {
+    let mut tracked = random_soup_in_corner(200, 170, 66, 56, 3);
+    let mut full_scan = random_soup_in_corner(200, 170, 66, 56, 3);
+    full_scan.set_active_tiles_tracking(false);
+    let mut total_skipped = 0;
+    for generation in 0..300 {
+        if generation == 150 {
+            // Modifications made between ticks are taken into account.
+            for universe in [&mut tracked, &mut full_scan].iter_mut() {
+                universe.make_glider(50, 50);
+                universe.set_cell(0, 0, Cell::Alive).unwrap();
+                universe.toggle_cell(199, 169);
+            }
+        }
+        tracked.tick();
+        full_scan.tick();
+        assert_eq!(tracked.get_cells(), full_scan.get_cells());
+        assert_eq!(tracked.changed_cells_len(), full_scan.changed_cells_len());
+        assert_eq!(full_scan.skipped_tiles(), 0);
+        total_skipped += tracked.skipped_tiles();
+    }
+    assert!(total_skipped > 0);
+}
+
+#[test]
+pub fn test_same_result_after_rule_changes() {
+    // Tiles which stayed the same by chance under another rule are computed
+    // again once the Game of Life resumes.
+    let setups: [fn(&mut Universe); 3] = [
+        |universe| universe.set_birth_probability(0.),
+        |universe| universe.set_noise_rate(0.01),
+        |universe| universe.set_margolus_preset(MargolusPreset::Critters).unwrap(),
+    ];
+    for setup in setups.iter() {
+        let mut tracked = Universe::new(64, 64);
+        tracked.set_cells(&[(10, 10), (10, 11), (11, 10)]);
+        let mut full_scan = Universe::new(64, 64);
+        full_scan.set_cells(&[(10, 10), (10, 11), (11, 10)]);
+        full_scan.set_active_tiles_tracking(false);
+        for universe in [&mut tracked, &mut full_scan].iter_mut() {
+            setup(universe);
+            universe.tick();
+            universe.tick();
+            universe.set_birth_probability(1.);
+            universe.set_noise_rate(0.);
+            universe.remove_margolus_rule();
+            universe.tick();
+        }
+        assert_eq!(tracked.get_cells(), full_scan.get_cells());
+    }
+}
+
+#[test]
+pub fn test_same_result_with_stochastic_rules() {
+    let mut tracked = random_soup_in_corner(100, 100, 40, 40, 8);
+    let mut full_scan = random_soup_in_corner(100, 100, 40, 40, 8);
+    full_scan.set_active_tiles_tracking(false);
+    for universe in [&mut tracked, &mut full_scan].iter_mut() {
+        universe.set_seed(21);
+        universe.set_birth_probability(0.8);
+        universe.set_survival_probability(0.9);
+        universe.set_noise_rate(0.001);
+    }
+    for _ in 0..60 {
+        tracked.tick();
+        full_scan.tick();
+        assert_eq!(tracked.get_cells(), full_scan.get_cells());
+    }
+}
+
+#[test]
+pub fn test_stable_tiles_are_skipped() {
+    let mut universe = Universe::new(128, 128);
+    universe.set_cells(&[(40, 40), (40, 41), (40, 42)]);
+
+    // Every tile is computed the first time.
+    universe.tick();
+    assert_eq!(universe.computed_tiles(), 16);
+    assert_eq!(universe.skipped_tiles(), 0);
+
+    // Then only the blinker's tile and those around it.
+    universe.tick();
+    assert_eq!(universe.computed_tiles(), 9);
+    assert_eq!(universe.skipped_tiles(), 7);
+
+    // Changing a cell far away wakes up its tile and those around it, some
+    // of them being shared with the blinker's.
+    universe.set_cell(120, 120, Cell::Alive).unwrap();
+    universe.tick();
+    assert_eq!(universe.computed_tiles(), 14);
+    assert_eq!(universe.get_cell(120, 120), Ok(Cell::Dead));
+}
@@ -0,0 +1,79 @@
+//! Tests for the tracking of the cells' ages and of the heat map.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::Universe;
+
+/// Returns the age of every cell.
+fn ages(universe: &Universe) -> Vec<u16> {
+    let len = (universe.height() * universe.width()) as usize;
+    unsafe { std::slice::from_raw_parts(universe.get_ages_ptr(), len).to_vec() }
+}
+
+/// Returns the heat of every cell.
+fn heat(universe: &Universe) -> Vec<u8> {
+    let len = (universe.height() * universe.width()) as usize;
+    unsafe { std::slice::from_raw_parts(universe.get_heat_ptr(), len).to_vec() }
+}
+
+#[test]
+pub fn test_ages_of_blinker() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.set_age_tracking(true);
+    assert!(ages(&universe).iter().all(|&age| age == 0));
+
+    for generation in 1..=4 {
+        universe.tick();
+        let ages = ages(&universe);
+
+        // The center never dies while the other cells only live one tick.
+        assert_eq!(ages[2 * 5 + 2], generation);
+        let (alive, dead) = if generation % 2 == 1 {
+            ([7, 17], [11, 13])
+        } else {
+            ([11, 13], [7, 17])
+        };
+        assert!(alive.iter().all(|&idx| ages[idx] == 1));
+        assert!(dead.iter().all(|&idx| ages[idx] == 0));
+    }
+}
+
+#[test]
+pub fn test_heat_map_decays() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.set_heat_tracking(true);
+    universe.set_heat_decay(100);
+    universe.tick();
+    let first_heat = heat(&universe);
+    for &idx in [7, 11, 13, 17].iter() {
+        assert_eq!(first_heat[idx], 255);
+    }
+    assert_eq!(first_heat[12], 0);
+
+    // Once the blinker is removed, its trail fades away.
+    universe.clear();
+    universe.tick();
+    assert_eq!(heat(&universe)[7], 155);
+    universe.tick();
+    assert_eq!(heat(&universe)[7], 55);
+    universe.tick();
+    assert!(heat(&universe).iter().all(|&heat| heat == 0));
+}
+
+#[test]
+pub fn test_activity_follows_resize() {
+    let mut universe = Universe::new(4, 4);
+    universe.set_age_tracking(true);
+    universe.set_heat_tracking(true);
+    universe.set_cells(&[(0, 0), (0, 1), (1, 0), (1, 1)]);
+    universe.tick();
+    universe.expand(2).unwrap();
+    assert_eq!(ages(&universe).len(), 64);
+    assert!(ages(&universe).iter().all(|&age| age == 0));
+    universe.tick();
+    let ages = ages(&universe);
+    assert_eq!(ages[2 * 8 + 2], 1);
+    assert_eq!(ages.iter().filter(|&&age| age > 0).count(), 4);
+}
@@ -0,0 +1,53 @@
+//! Tests for reading and writing individual cells.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Cell, Universe, UniverseError};
+
+#[test]
+pub fn test_get_and_set_cell() {
+    let mut universe = Universe::new(3, 4);
+    assert_eq!(universe.get_cell(1, 2), Ok(Cell::Dead));
+
+    // Setting a cell does not depend on its current state.
+    universe.set_cell(1, 2, Cell::Alive).unwrap();
+    universe.set_cell(1, 2, Cell::Alive).unwrap();
+    assert_eq!(universe.get_cell(1, 2), Ok(Cell::Alive));
+    universe.set_cell(1, 2, Cell::Dead).unwrap();
+    assert_eq!(universe.get_cell(1, 2), Ok(Cell::Dead));
+
+    let out_of_bounds = UniverseError::OutOfBounds {
+        row: 3,
+        column: 0,
+        height: 3,
+        width: 4,
+    };
+    assert_eq!(universe.get_cell(3, 0), Err(out_of_bounds));
+    assert_eq!(universe.set_cell(3, 0, Cell::Alive), Err(out_of_bounds));
+}
+
+#[test]
+pub fn test_set_cells_from_flat_and_clear() {
+    let mut universe = Universe::new(3, 4);
+    universe.set_cells_from_flat(&[0, 5, 11], Cell::Alive).unwrap();
+    assert_eq!(universe.get_cell(0, 0), Ok(Cell::Alive));
+    assert_eq!(universe.get_cell(1, 1), Ok(Cell::Alive));
+    assert_eq!(universe.get_cell(2, 3), Ok(Cell::Alive));
+
+    universe.set_cells_from_flat(&[5, 6], Cell::Dead).unwrap();
+    assert_eq!(universe.get_cell(1, 1), Ok(Cell::Dead));
+    assert_eq!(universe.get_cell(1, 2), Ok(Cell::Dead));
+
+    // Nothing is updated if one index is out of bounds.
+    assert_eq!(universe.set_cells_from_flat(&[1, 12], Cell::Alive),
+               Err(UniverseError::OutOfBounds {
+                   row: 3,
+                   column: 0,
+                   height: 3,
+                   width: 4,
+               }));
+    assert_eq!(universe.get_cell(0, 1), Ok(Cell::Dead));
+
+    universe.clear();
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+}
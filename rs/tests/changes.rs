@@ -0,0 +1,69 @@
+//! Tests for the list of cells changed by the last tick.
+
+extern crate wasm_game_of_life;
+
+use std::slice;
+use wasm_game_of_life::{Cell, MargolusPreset, Universe};
+
+/// Returns the index and new state of every cell changed by the last tick.
+fn changes(universe: &Universe) -> Vec<(u32, Cell)> {
+    let len = universe.changed_cells_len() as usize;
+    let (indices, states) = unsafe {
+        (slice::from_raw_parts(universe.get_changed_cells_ptr(), len),
+         slice::from_raw_parts(universe.get_changed_states_ptr(), len))
+    };
+    indices.iter().cloned().zip(states.iter().cloned()).collect()
+}
+
+#[test]
+pub fn test_blinker_changes() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    assert_eq!(universe.changed_cells_len(), 0);
+
+    universe.tick();
+    assert_eq!(changes(&universe), vec![(7, Cell::Dead),
+                                        (11, Cell::Alive),
+                                        (13, Cell::Alive),
+                                        (17, Cell::Dead)]);
+
+    // Applying the changes to the previous generation gives the current one.
+    let mut previous_cells = Universe::new(5, 5);
+    previous_cells.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.tick();
+    let mut cells = previous_cells.get_cells().to_vec();
+    for (idx, state) in changes(&universe) {
+        cells[idx as usize] = state;
+    }
+    assert_eq!(&cells[..], universe.get_cells());
+}
+
+#[test]
+pub fn test_still_life_and_reverse_tick_changes() {
+    let mut universe = Universe::new(6, 6);
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.tick();
+    assert_eq!(changes(&universe), vec![]);
+
+    let mut universe = Universe::new(6, 6);
+    universe.set_margolus_preset(MargolusPreset::BilliardBallMachine).unwrap();
+    universe.set_cells(&[(0, 0)]);
+    universe.tick();
+    assert_eq!(changes(&universe), vec![(0, Cell::Dead), (7, Cell::Alive)]);
+    universe.reverse_tick().unwrap();
+    assert_eq!(changes(&universe), vec![(0, Cell::Alive), (7, Cell::Dead)]);
+}
+
+#[test]
+pub fn test_changed_cells_slices_and_population() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    assert_eq!(universe.population(), 3);
+
+    universe.tick();
+    let slices: Vec<(u32, Cell)> = universe.changed_cells().iter().cloned()
+        .zip(universe.changed_states().iter().cloned())
+        .collect();
+    assert_eq!(slices, changes(&universe));
+    assert_eq!(universe.population(), 3);
+}
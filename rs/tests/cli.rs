@@ -0,0 +1,116 @@
+//! Tests for the `gol` command-line interface.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Run `gol` with the given arguments and standard input, and returns its
+/// exit code and standard output.
+fn gol(args: &[&str], input: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gol"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+pub fn test_run() {
+    let (code, output) = gol(&["run", "-n", "4", "--margin", "1"], "x = 3, y = 3\nbo$2bo$3o!\n");
+    assert_eq!(code, 0);
+    assert_eq!(output, "x = 5, y = 5, rule = B3/S23\n2$3bo$4bo$2b3o!\n");
+}
+
+#[test]
+pub fn test_run_bounded() {
+    // A glider reaching a corner becomes a block.
+    let glider = "x = 4, y = 4\nbo$2bo$3o!\n";
+    let (code, output) = gol(&["run", "-n", "8", "-t", "bounded", "-f", "plaintext"], glider);
+    assert_eq!(code, 0);
+    assert_eq!(output, "....\n....\n..OO\n..OO\n");
+}
+
+#[test]
+pub fn test_convert() {
+    let (code, output) = gol(&["convert", "-f", "plaintext", "-"], "x = 3, y = 2\n3o$bo!\n");
+    assert_eq!(code, 0);
+    assert_eq!(output, "OOO\n.O.\n");
+
+    let (code, output) = gol(&["convert"], "OOO\n.O.\n");
+    assert_eq!(code, 0);
+    assert_eq!(output, "x = 3, y = 2\n3o$bo!\n");
+}
+
+#[test]
+pub fn test_snapshot() {
+    // A run resumed from a snapshot keeps its settings.
+    let path = std::env::temp_dir().join(format!("gol_snapshot_{}.snap", std::process::id()));
+    let path = path.to_str().unwrap();
+    let glider = "x = 3, y = 3\nbo$2bo$3o!\n";
+    let args = ["run", "-n", "4", "-m", "1", "-t", "bounded", "-o", path];
+    assert_eq!(gol(&args, glider).0, 0);
+    let (code, output) = gol(&["run", "-n", "4", "-f", "plaintext", path], "");
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(code, 0);
+    assert_eq!(output, ".....\n.....\n.....\n...OO\n...OO\n");
+}
+
+#[test]
+pub fn test_macrocell() {
+    // Only the live cells of a Macrocell pattern are read.
+    let macrocell = "[M2] (golly 4.2)\n#R B3/S23\n.*$..*$***$\n4 0 0 0 1\n";
+    let (code, output) = gol(&["convert"], macrocell);
+    assert_eq!(code, 0);
+    assert_eq!(output, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+
+    let args = ["run", "-n", "4", "-m", "1", "-f", "macrocell"];
+    let (code, output) = gol(&args, "x = 3, y = 3\nbo$2bo$3o!\n");
+    assert_eq!(code, 0);
+    assert_eq!(output, "[M2] (wasm-game-of-life)\n#R B3/S23\n$$...*$....*$..***$\n");
+}
+
+#[test]
+pub fn test_svg() {
+    let (code, output) = gol(&["run", "-n", "1", "-m", "1", "-f", "svg", "--cell-size", "2"],
+                             ".O.\n.O.\n.O.\n");
+    assert_eq!(code, 0);
+    assert!(output.contains("data-generation=\"1\""));
+    assert!(output.contains("<rect x=\"2\" y=\"4\" width=\"6\" height=\"2\"/>"));
+}
+
+#[test]
+pub fn test_stats() {
+    let (code, output) = gol(&["stats", "-n", "2"], ".....\n..O..\n..O..\n..O..\n.....\n");
+    assert_eq!(code, 0);
+    assert_eq!(output, "generation\tpopulation\tbirths\tdeaths\n\
+                        0\t3\t0\t0\n\
+                        1\t3\t2\t2\n\
+                        2\t3\t2\t2\n");
+}
+
+#[test]
+pub fn test_record() {
+    let path = std::env::temp_dir().join(format!("gol_record_{}.gif", std::process::id()));
+    let path = path.to_str().unwrap();
+    let (code, _) = gol(&["record", "-n", "3", "--cell-size", "2", "-o", path], "OO\nOO\n");
+    let gif = std::fs::read(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(code, 0);
+    assert_eq!(&gif[..10], b"GIF89a\x04\x00\x04\x00");
+    assert_eq!(gif.last(), Some(&0x3B));
+}
+
+#[test]
+pub fn test_errors() {
+    assert_eq!(gol(&[], "").0, 2);
+    assert_eq!(gol(&["run", "--rule", "unknown"], "").0, 2);
+    assert_eq!(gol(&["run", "-n"], "").0, 2);
+    assert_eq!(gol(&["record", "--palette", "sepia"], "").0, 2);
+    assert_eq!(gol(&["run"], "x = 3\n3o!").0, 1);
+    assert_eq!(gol(&["run"], "x = 3, y = 1, rule = B36/S23\n3o!\n").0, 1);
+    assert_eq!(gol(&["run", "--rule", "life"], "x = 3, y = 1, rule = B36/S23\n3o!\n").0, 0);
+}
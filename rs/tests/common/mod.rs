@@ -0,0 +1,36 @@
+//! Fixtures shared by the tests.
+
+#![allow(dead_code)]
+
+use wasm_game_of_life::Universe;
+
+/// Fill a Universe with a pseudo-random soup, always the same for a given
+/// seed.
+pub fn random_soup(height: u32, width: u32, seed: u32) -> Universe {
+    random_soup_in_corner(height, width, height, width, seed)
+}
+
+/// Create a Universe with a pseudo-random soup in its `soup_height` first
+/// rows and `soup_width` first columns, always the same for a given seed.
+/// Its other cells are dead.
+pub fn random_soup_in_corner(
+    height: u32,
+    width: u32,
+    soup_height: u32,
+    soup_width: u32,
+    seed: u32
+) -> Universe {
+    let mut universe = Universe::new(height, width);
+    let mut state = seed;
+    let mut cells = Vec::new();
+    for row in 0..soup_height {
+        for col in 0..soup_width {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            if (state >> 16) & 1 == 1 {
+                cells.push((row, col));
+            }
+        }
+    }
+    universe.set_cells(&cells);
+    universe
+}
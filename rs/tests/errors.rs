@@ -0,0 +1,76 @@
+//! Tests for the errors returned by the fallible API.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Universe, UniverseError};
+
+#[test]
+pub fn test_invalid_dimensions() {
+    assert_eq!(Universe::try_new(0, 5).err(),
+               Some(UniverseError::EmptyDimension { height: 0, width: 5 }));
+    assert_eq!(Universe::try_new(5, 0).err(),
+               Some(UniverseError::EmptyDimension { height: 5, width: 0 }));
+    assert_eq!(Universe::try_new(1 << 16, 1 << 16).err(),
+               Some(UniverseError::TooLarge { height: 1 << 16, width: 1 << 16 }));
+    assert!(Universe::try_new(1, 1).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "cannot have an empty dimension")]
+pub fn test_new_panics_with_empty_dimension() {
+    Universe::new(0, 10);
+}
+
+#[test]
+pub fn test_out_of_bounds_cells() {
+    let mut universe = Universe::new(4, 6);
+    let out_of_bounds = UniverseError::OutOfBounds {
+        row: 4,
+        column: 2,
+        height: 4,
+        width: 6,
+    };
+    assert_eq!(universe.try_toggle_cell(4, 2), Err(out_of_bounds));
+    assert_eq!(universe.try_make_glider(4, 2), Err(out_of_bounds));
+
+    // Nothing is set if one of the cells is out of bounds.
+    assert_eq!(universe.try_set_cells(&[(0, 0), (4, 2)]), Err(out_of_bounds));
+    assert_eq!(universe.to_string(), Universe::new(4, 6).to_string());
+
+    assert_eq!(universe.try_toggle_cell(3, 5), Ok(()));
+    assert_eq!(universe.try_make_glider(0, 0), Ok(()));
+}
+
+#[test]
+pub fn test_pulsar_out_of_small_universes() {
+    let mut universe = Universe::new(6, 20);
+    assert_eq!(universe.try_make_pulsar(3, 10), Err(UniverseError::OutOfBounds {
+        row: 6,
+        column: 6,
+        height: 6,
+        width: 20,
+    }));
+    assert_eq!(universe.to_string(), Universe::new(6, 20).to_string());
+
+    let mut universe = Universe::new(7, 7);
+    assert_eq!(universe.try_make_pulsar(7, 0), Err(UniverseError::OutOfBounds {
+        row: 7,
+        column: 0,
+        height: 7,
+        width: 7,
+    }));
+    assert_eq!(universe.try_make_pulsar(3, 3), Ok(()));
+}
+
+#[test]
+pub fn test_error_messages() {
+    let err = UniverseError::OutOfBounds {
+        row: 10,
+        column: 3,
+        height: 8,
+        width: 8,
+    };
+    assert_eq!(err.to_string(),
+               "Cell at row 10 and column 3 is out of the Universe's \
+                boundaries (8x8)");
+}
@@ -0,0 +1,98 @@
+//! Tests for rendering a Universe into a buffer of RGBA pixels.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Framebuffer, Universe};
+
+const ALIVE: [u8; 4] = [0x00, 0x00, 0x00, 0xFF];
+const DEAD: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+const GRID: [u8; 4] = [0xCC, 0xCC, 0xCC, 0xFF];
+
+/// Returns the RGBA bytes of the pixel at the given coordinates.
+fn pixel(framebuffer: &Framebuffer, x: u32, y: u32) -> [u8; 4] {
+    let pixels = unsafe {
+        std::slice::from_raw_parts(framebuffer.pixels_ptr(),
+                                   framebuffer.pixels_len() as usize)
+    };
+    let idx = ((y * framebuffer.width() + x) * 4) as usize;
+    [pixels[idx], pixels[idx + 1], pixels[idx + 2], pixels[idx + 3]]
+}
+
+#[test]
+pub fn test_render_with_grid_lines() {
+    let mut universe = Universe::new(2, 3);
+    universe.set_cells(&[(0, 1), (1, 2)]);
+    let mut framebuffer = Framebuffer::new();
+    framebuffer.set_cell_size(2);
+    framebuffer.render(&universe).unwrap();
+
+    assert_eq!(framebuffer.width(), 10);
+    assert_eq!(framebuffer.height(), 7);
+    assert_eq!(framebuffer.pixels_len(), 10 * 7 * 4);
+    let expected_rows = [
+        "GGGGGGGGGG",
+        "GDDGAAGDDG",
+        "GDDGAAGDDG",
+        "GGGGGGGGGG",
+        "GDDGDDGAAG",
+        "GDDGDDGAAG",
+        "GGGGGGGGGG",
+    ];
+    for (y, expected_row) in expected_rows.iter().enumerate() {
+        for (x, expected) in expected_row.chars().enumerate() {
+            let expected = match expected {
+                'A' => ALIVE,
+                'D' => DEAD,
+                _ => GRID,
+            };
+            assert_eq!(pixel(&framebuffer, x as u32, y as u32), expected,
+                       "pixel at ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+pub fn test_render_without_grid_lines() {
+    let mut universe = Universe::new(3, 2);
+    universe.set_cells(&[(2, 0)]);
+    let mut framebuffer = Framebuffer::new();
+    framebuffer.set_grid_lines(false);
+    framebuffer.set_cell_size(3);
+    framebuffer.set_alive_color(0x11223344);
+    framebuffer.set_dead_color(0x55667788);
+    framebuffer.render(&universe).unwrap();
+
+    assert_eq!(framebuffer.width(), 6);
+    assert_eq!(framebuffer.height(), 9);
+    for y in 0..9 {
+        for x in 0..6 {
+            let expected = if y >= 6 && x < 3 {
+                [0x11, 0x22, 0x33, 0x44]
+            } else {
+                [0x55, 0x66, 0x77, 0x88]
+            };
+            assert_eq!(pixel(&framebuffer, x, y), expected);
+        }
+    }
+}
+
+#[test]
+pub fn test_render_follows_universe() {
+    let mut universe = Universe::new(4, 4);
+    let mut framebuffer = Framebuffer::new();
+    framebuffer.render(&universe).unwrap();
+    assert_eq!(pixel(&framebuffer, 1, 1), DEAD);
+
+    universe.toggle_cell(0, 0);
+    framebuffer.render(&universe).unwrap();
+    assert_eq!(pixel(&framebuffer, 1, 1), ALIVE);
+    assert_eq!(pixel(&framebuffer, 8, 8), ALIVE);
+    assert_eq!(pixel(&framebuffer, 9, 9), GRID);
+
+    // The image is resized along with the Universe.
+    universe.expand(1).unwrap();
+    framebuffer.render(&universe).unwrap();
+    assert_eq!(framebuffer.width(), 6 * 9 + 1);
+    assert_eq!(pixel(&framebuffer, 1, 1), DEAD);
+    assert_eq!(pixel(&framebuffer, 10, 10), ALIVE);
+}
@@ -0,0 +1,266 @@
+//! Tests for the recording of animated GIFs.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{GifPalette, GifRecorder, Universe, UniverseError};
+
+/// Frames of a decoded GIF file.
+struct Gif {
+    width: usize,
+    height: usize,
+    palette: Vec<[u8; 3]>,
+    looping: bool,
+
+    /// Delay and whole image of each frame, as indices in the palette, after
+    /// being drawn over the previous ones.
+    frames: Vec<(u16, Vec<u8>)>,
+}
+
+/// Decode a GIF file as written by a `GifRecorder`.
+fn decode(bytes: &[u8]) -> Gif {
+    assert_eq!(&bytes[..6], b"GIF89a");
+    let word = |idx: usize| usize::from(u16::from_le_bytes([bytes[idx], bytes[idx + 1]]));
+    let (width, height) = (word(6), word(8));
+    let nb_colors = 2 << (bytes[10] & 0x07);
+    let palette = bytes[13..13 + 3 * nb_colors].chunks_exact(3)
+        .map(|color| [color[0], color[1], color[2]])
+        .collect();
+    let mut gif = Gif { width, height, palette, looping: false, frames: Vec::new() };
+
+    let mut canvas = vec![0; width * height];
+    let mut delay = 0;
+    let mut idx = 13 + 3 * nb_colors;
+    loop {
+        match bytes[idx] {
+            0x3B => return gif,
+            0x21 => {
+                match bytes[idx + 1] {
+                    0xF9 => delay = word(idx + 4) as u16,
+                    0xFF => gif.looping = &bytes[idx + 3..idx + 14] == b"NETSCAPE2.0",
+                    _ => panic!("unexpected extension"),
+                }
+                idx += 2;
+                while bytes[idx] != 0 {
+                    idx += usize::from(bytes[idx]) + 1;
+                }
+                idx += 1;
+            }
+            0x2C => {
+                let (left, top) = (word(idx + 1), word(idx + 3));
+                let (frame_width, frame_height) = (word(idx + 5), word(idx + 7));
+                let min_code_size = bytes[idx + 10];
+                idx += 11;
+                let mut data = Vec::new();
+                while bytes[idx] != 0 {
+                    let len = usize::from(bytes[idx]);
+                    data.extend_from_slice(&bytes[idx + 1..idx + 1 + len]);
+                    idx += len + 1;
+                }
+                idx += 1;
+                let pixels = lzw_decompress(min_code_size, &data);
+                assert_eq!(pixels.len(), frame_width * frame_height);
+                for (row, line) in pixels.chunks_exact(frame_width).enumerate() {
+                    let start = (top + row) * width + left;
+                    canvas[start..start + frame_width].copy_from_slice(line);
+                }
+                gif.frames.push((delay, canvas.clone()));
+            }
+            other => panic!("unexpected block {:#x}", other),
+        }
+    }
+}
+
+/// Decompress the LZW data of a frame.
+fn lzw_decompress(min_code_size: u8, data: &[u8]) -> Vec<u8> {
+    let clear_code = 1usize << min_code_size;
+    let reset = || -> Vec<Vec<u8>> {
+        (0..clear_code + 2).map(|idx| vec![idx as u8]).collect()
+    };
+    let mut dictionary = reset();
+    let mut code_size = u32::from(min_code_size) + 1;
+    let mut previous: Option<Vec<u8>> = None;
+    let mut pixels = Vec::new();
+    let (mut buffer, mut nb_bits, mut bytes) = (0u32, 0, data.iter());
+    loop {
+        while nb_bits < code_size {
+            buffer |= u32::from(*bytes.next().unwrap()) << nb_bits;
+            nb_bits += 8;
+        }
+        let code = (buffer & ((1 << code_size) - 1)) as usize;
+        buffer >>= code_size;
+        nb_bits -= code_size;
+        if code == clear_code {
+            dictionary = reset();
+            code_size = u32::from(min_code_size) + 1;
+            previous = None;
+            continue;
+        } else if code == clear_code + 1 {
+            return pixels;
+        }
+        let sequence = match previous {
+            None => dictionary[code].clone(),
+            Some(previous) => {
+                let sequence = match dictionary.get(code) {
+                    Some(sequence) => sequence.clone(),
+                    None => {
+                        assert_eq!(code, dictionary.len());
+                        let mut sequence = previous.clone();
+                        sequence.push(previous[0]);
+                        sequence
+                    }
+                };
+                if dictionary.len() < 4096 {
+                    let mut entry = previous;
+                    entry.push(sequence[0]);
+                    dictionary.push(entry);
+                    if dictionary.len() == 1 << code_size && code_size < 12 {
+                        code_size += 1;
+                    }
+                }
+                sequence
+            }
+        };
+        pixels.extend_from_slice(&sequence);
+        previous = Some(sequence);
+    }
+}
+
+/// Returns the cells shown by an image of cells of `cell_size` pixels of side
+/// without grid lines, whose colour is alive if it is not the one given.
+fn cells(gif: &Gif, image: &[u8], cell_size: usize, dead_color: u8) -> Vec<(u32, u32)> {
+    let mut cells = Vec::new();
+    for row in 0..gif.height / cell_size {
+        for col in 0..gif.width / cell_size {
+            if image[row * cell_size * gif.width + col * cell_size] != dead_color {
+                cells.push((row as u32, col as u32));
+            }
+        }
+    }
+    cells
+}
+
+/// Returns the cells of a Universe which are alive.
+fn live_cells(universe: &Universe) -> Vec<(u32, u32)> {
+    let mut cells = Vec::new();
+    for row in 0..universe.height() {
+        for col in 0..universe.width() {
+            if universe.get_cell(row, col) == Ok(wasm_game_of_life::Cell::Alive) {
+                cells.push((row, col));
+            }
+        }
+    }
+    cells
+}
+
+/// Create a Universe filled with a pseudo-random soup.
+fn soup(height: u32, width: u32) -> Universe {
+    let mut universe = Universe::new(height, width);
+    let mut state = 7u32;
+    let mut cells = Vec::new();
+    for row in 0..height {
+        for col in 0..width {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            if (state >> 16) & 3 == 0 {
+                cells.push((row, col));
+            }
+        }
+    }
+    universe.set_cells(&cells);
+    universe
+}
+
+#[test]
+pub fn test_record() {
+    let mut universe = soup(60, 80);
+    let mut recorder = GifRecorder::new();
+    recorder.set_cell_size(3);
+    recorder.set_delay(250);
+    recorder.set_looping(false);
+
+    let mut reference = soup(60, 80);
+    reference.tick();
+    reference.tick();
+    let mut expected = Vec::new();
+    for _ in 2..=12 {
+        expected.push(live_cells(&reference));
+        reference.tick();
+    }
+
+    let gif = decode(&recorder.record(&mut universe, 2, 12).unwrap());
+    assert_eq!(universe.generation(), 12);
+    assert_eq!((gif.width, gif.height), (240, 180));
+    assert_eq!(gif.palette[..3], [[0xCC, 0xCC, 0xCC], [0xFF, 0xFF, 0xFF], [0, 0, 0]]);
+    assert!(!gif.looping);
+    assert_eq!(gif.frames.len(), 11);
+    for ((delay, image), expected) in gif.frames.iter().zip(&expected) {
+        assert_eq!(*delay, 25);
+        assert_eq!(&cells(&gif, image, 3, 1), expected);
+    }
+}
+
+#[test]
+pub fn test_crop_and_grid() {
+    let mut universe = Universe::new(10, 10);
+    universe.set_cells(&[(3, 4), (4, 5), (5, 3), (5, 4), (5, 5)]);
+    let mut recorder = GifRecorder::new();
+    recorder.set_cell_size(2);
+    recorder.set_grid_lines(true);
+    recorder.set_crop(3, 3, 3, 20);
+
+    let gif = decode(&recorder.record(&mut universe, 0, 0).unwrap());
+    assert!(gif.looping);
+    assert_eq!((gif.width, gif.height), (7 * 3 + 1, 3 * 3 + 1));
+    let (_, image) = &gif.frames[0];
+    let row = |idx: usize| &image[idx * gif.width..(idx + 1) * gif.width];
+    assert!(row(0).iter().all(|&pixel| pixel == 0));
+    assert_eq!(row(1)[..10], [0, 1, 1, 0, 2, 2, 0, 1, 1, 0]);
+    assert_eq!(row(1), row(2));
+    assert_eq!(row(7)[..10], [0, 2, 2, 0, 2, 2, 0, 2, 2, 0]);
+}
+
+#[test]
+pub fn test_unchanged_frames() {
+    // Frames of a still life keep their delay, drawing a single pixel.
+    let mut universe = Universe::new(4, 4);
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    let gif = GifRecorder::new().record(&mut universe, 0, 3).unwrap();
+    let decoded = decode(&gif);
+    assert_eq!(decoded.frames.len(), 4);
+    assert!(decoded.frames.iter().all(|frame| *frame == decoded.frames[0]));
+    assert!(gif.len() < 200);
+}
+
+#[test]
+pub fn test_age_palette() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_age_tracking(true);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    let mut recorder = GifRecorder::new();
+    recorder.set_cell_size(1);
+    recorder.set_palette(GifPalette::Age);
+
+    let gif = decode(&recorder.record(&mut universe, 1, 2).unwrap());
+    assert_eq!(gif.palette[1], [0, 0, 0]);
+    // After the second tick, the centre of the blinker is older.
+    let (_, image) = &gif.frames[1];
+    assert_eq!(image[2 * 5 + 1..2 * 5 + 4], [2, 3, 2]);
+}
+
+#[test]
+pub fn test_errors() {
+    let mut universe = soup(10, 10);
+    universe.tick();
+    let mut recorder = GifRecorder::new();
+    assert_eq!(recorder.record(&mut universe, 0, 5),
+               Err(UniverseError::InvalidGenerationRange { first: 0, last: 5, current: 1 }));
+    assert_eq!(recorder.record(&mut universe, 5, 4),
+               Err(UniverseError::InvalidGenerationRange { first: 5, last: 4, current: 1 }));
+    recorder.set_crop(10, 0, 5, 5);
+    assert_eq!(recorder.record(&mut universe, 1, 1),
+               Err(UniverseError::EmptyDimension { height: 0, width: 5 }));
+    recorder.clear_crop();
+    recorder.set_cell_size(10_000);
+    assert_eq!(recorder.record(&mut universe, 1, 1),
+               Err(UniverseError::ImageTooLarge { height: 10, width: 10 }));
+    assert_eq!(universe.generation(), 1);
+}
@@ -0,0 +1,51 @@
+//! Tests for the lookup table engine, which should always give the same
+//! results than counting neighbours.
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use wasm_game_of_life::{TickEngine, Universe};
+use common::random_soup;
+
+#[test]
+pub fn test_default_engine() {
+    let universe = Universe::new(8, 8);
+    assert_eq!(universe.tick_engine(), TickEngine::Loop);
+}
+
+#[test]
+pub fn test_same_result_than_loop() {
+    let dimensions = [(1, 1), (1, 7), (7, 1), (2, 2), (3, 5), (23, 17), (64, 48)];
+    for (seed, &(height, width)) in dimensions.iter().enumerate() {
+        let mut table = random_soup(height, width, seed as u32);
+        table.set_tick_engine(TickEngine::LookupTable);
+        assert_eq!(table.tick_engine(), TickEngine::LookupTable);
+        let mut counting = random_soup(height, width, seed as u32);
+        for _ in 0..50 {
+            table.tick();
+            counting.tick();
+            assert_eq!(table.get_cells(), counting.get_cells(),
+                       "{}x{} universe", height, width);
+            assert_eq!(table.changed_cells_len(), counting.changed_cells_len());
+        }
+    }
+}
+
+#[test]
+pub fn test_switch_engine() {
+    let mut universe = Universe::new(16, 16);
+    universe.make_glider(2, 2);
+    let expected = universe.get_cells().to_vec();
+    universe.set_tick_engine(TickEngine::LookupTable);
+    for _ in 0..4 {
+        universe.tick();
+    }
+    universe.set_tick_engine(TickEngine::Loop);
+    for _ in 0..60 {
+        universe.tick();
+    }
+
+    // After 64 generations, a glider is back in place on a 16x16 torus.
+    assert_eq!(universe.get_cells(), &expected[..]);
+}
@@ -0,0 +1,125 @@
+//! Tests for quadtrees and the Macrocell format.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Cell, MacrocellError, Pattern, Quadtree, Universe, UniverseError};
+
+const GLIDER_MACROCELL: &str = "\
+[M2] (golly 4.2)
+#R B3/S23
+#C A glider in the south-east quarter.
+.*$..*$***$
+4 0 0 0 1
+";
+
+/// Returns a Macrocell file of a tree of the given level, whose 8x8 blocks
+/// all contain a glider.
+fn gliders(level: u32) -> String {
+    let mut text = String::from("[M2]\n.*$..*$***$\n");
+    for (number, level) in (4..=level).enumerate() {
+        let child = number + 1;
+        text.push_str(&format!("{} {} {} {} {}\n", level, child, child, child, child));
+    }
+    text
+}
+
+#[test]
+pub fn test_read() {
+    let tree = Quadtree::from_macrocell(GLIDER_MACROCELL).unwrap();
+    assert_eq!(tree.level(), 4);
+    assert_eq!(tree.rule(), Some("B3/S23".to_owned()));
+    assert_eq!(tree.population(), 5);
+    assert_eq!(tree.bounding_box(), Some((8, 8, 3, 3)));
+    assert_eq!(tree.get(8, 9), Cell::Alive);
+    assert_eq!(tree.get(9, 9), Cell::Dead);
+    assert_eq!(tree.get(16, 9), Cell::Dead);
+
+    let pattern = tree.to_pattern(7, 8, 3, 4).unwrap();
+    assert_eq!(pattern.to_plaintext(), "....\n.O..\n..O.\n");
+    let universe = Universe::from_quadtree(&tree).unwrap();
+    assert_eq!((universe.height(), universe.width()), (3, 3));
+    assert_eq!(universe.population(), 5);
+}
+
+#[test]
+pub fn test_write() {
+    let mut pattern = Pattern::new(10, 12).unwrap();
+    for &(row, col) in &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2), (9, 11)] {
+        pattern.set(row, col, Cell::Alive);
+    }
+    pattern.set_rule(Some("B3/S23".to_owned()));
+    let tree = Quadtree::from_pattern(&pattern);
+    assert_eq!(tree.level(), 4);
+    assert_eq!(tree.to_macrocell(), "\
+[M2] (wasm-game-of-life)
+#R B3/S23
+.*$..*$***$
+$...*$
+4 1 0 0 2
+");
+    let read = Quadtree::from_macrocell(&tree.to_macrocell()).unwrap();
+    assert_eq!(read.to_pattern(0, 0, 10, 12).unwrap(), pattern);
+
+    let empty = Quadtree::from_pattern(&Pattern::new(3, 3).unwrap());
+    assert_eq!(empty.to_macrocell(), "[M2] (wasm-game-of-life)\n$\n");
+    assert_eq!(Quadtree::from_macrocell(&empty.to_macrocell()).unwrap().population(), 0);
+}
+
+#[test]
+pub fn test_shared_nodes() {
+    // Identical blocks are written once.
+    let mut universe = Universe::new(256, 256);
+    for row in (0..256).step_by(8) {
+        for col in (0..256).step_by(8) {
+            universe.make_glider(row + 1, col + 1);
+        }
+    }
+    let tree = Quadtree::from_pattern(&universe.to_pattern());
+    assert_eq!(tree.population(), 5 * 32 * 32);
+    assert_eq!(tree.to_macrocell().lines().count(), 1 + 1 + 5);
+    let read = Quadtree::from_macrocell(&tree.to_macrocell()).unwrap();
+    assert_eq!(read.to_pattern(0, 0, 256, 256).unwrap(), universe.to_pattern());
+}
+
+#[test]
+pub fn test_huge_tree() {
+    let tree = Quadtree::from_macrocell(&gliders(30)).unwrap();
+    assert_eq!(tree.level(), 30);
+    assert_eq!(tree.population(), 5 << 54);
+    let side = 1 << 30;
+    assert_eq!(tree.bounding_box(), Some((0, 0, side - 5, side - 5)));
+    assert_eq!(tree.get(side - 8, side - 7), Cell::Alive);
+    assert_eq!(tree.get(side, 1), Cell::Dead);
+
+    // Only regions which fit in a Universe can be flattened.
+    let pattern = tree.to_pattern(side - 9, side - 9, 4, 4).unwrap();
+    assert_eq!(pattern.to_plaintext(), "....\n..O.\n...O\n.OOO\n");
+    assert_eq!(tree.to_pattern(0, 0, 1 << 20, 1 << 20).err(), Some(MacrocellError::TooLarge));
+    assert_eq!(Universe::from_quadtree(&tree).err(), Some(MacrocellError::TooLarge));
+
+    assert_eq!(Quadtree::from_macrocell(&gliders(63)).unwrap().population(), u64::MAX);
+    assert_eq!(Quadtree::from_macrocell(&gliders(64)).err(), Some(MacrocellError::TooLarge));
+}
+
+#[test]
+pub fn test_errors() {
+    assert_eq!(Quadtree::from_macrocell("x = 3, y = 3\nbo$2bo$3o!\n").err(),
+               Some(MacrocellError::MissingHeader));
+    assert_eq!(Quadtree::from_macrocell("[M2]\n.*$..*$***$\n\n4 1 0 0\n").err(),
+               Some(MacrocellError::InvalidLine { line: 4 }));
+    assert_eq!(Quadtree::from_macrocell("[M2]\n.o$\n").err(),
+               Some(MacrocellError::InvalidLine { line: 2 }));
+    assert_eq!(Quadtree::from_macrocell("[M2]\n........*$\n").err(),
+               Some(MacrocellError::InvalidLine { line: 2 }));
+    // Nodes must refer to previous nodes of the level below theirs.
+    assert_eq!(Quadtree::from_macrocell("[M2]\n*$\n4 1 2 0 0\n").err(),
+               Some(MacrocellError::InvalidNode { line: 3 }));
+    assert_eq!(Quadtree::from_macrocell("[M2]\n*$\n4 1 0 0 0\n5 0 0 1 0\n").err(),
+               Some(MacrocellError::InvalidNode { line: 4 }));
+    assert_eq!(Quadtree::from_macrocell("[M2]\n3 0 0 0 0\n").err(),
+               Some(MacrocellError::InvalidNode { line: 2 }));
+
+    let empty = Quadtree::from_macrocell("[M2]\n").unwrap();
+    assert_eq!(Universe::from_quadtree(&empty).err(),
+               Some(MacrocellError::Universe(UniverseError::NoLiveCell)));
+}
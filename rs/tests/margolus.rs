@@ -0,0 +1,86 @@
+//! Tests for the Margolus block cellular automata.
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use wasm_game_of_life::{
+    Cell,
+    MargolusPreset,
+    MargolusRule,
+    Universe,
+    UniverseError,
+};
+use common::random_soup;
+
+#[test]
+pub fn test_tron_complements_uniform_blocks() {
+    let mut universe = Universe::new(4, 4);
+    universe.set_cells(&[(0, 2), (0, 3), (1, 2), (1, 3), (2, 0)]);
+    universe.set_margolus_preset(MargolusPreset::Tron).unwrap();
+    universe.tick();
+
+    let mut expected = Universe::new(4, 4);
+    expected.set_cells(&[(0, 0), (0, 1), (1, 0), (1, 1),
+                         (2, 0), (2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+    assert_eq!(universe.generation(), 1);
+}
+
+#[test]
+pub fn test_billiard_ball_moves_diagonally() {
+    let mut universe = Universe::new(6, 6);
+    universe.set_cells(&[(0, 0)]);
+    universe.set_margolus_preset(MargolusPreset::BilliardBallMachine)
+        .unwrap();
+
+    // Even phase: the ball goes to the opposite corner of its block.
+    universe.tick();
+    let mut expected = Universe::new(6, 6);
+    expected.set_cells(&[(1, 1)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    // Odd phase: the block now begins at (1, 1).
+    universe.tick();
+    let mut expected = Universe::new(6, 6);
+    expected.set_cells(&[(2, 2)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[test]
+pub fn test_reverse_tick_restores_previous_generations() {
+    let mut universe = random_soup(16, 20, 42);
+    let initial_cells = universe.get_cells().to_vec();
+    universe.set_margolus_preset(MargolusPreset::Critters).unwrap();
+    for _ in 0..25 {
+        universe.tick();
+    }
+    assert_ne!(universe.get_cells(), &initial_cells[..]);
+    for _ in 0..25 {
+        universe.reverse_tick().unwrap();
+    }
+    assert_eq!(universe.get_cells(), &initial_cells[..]);
+    assert_eq!(universe.generation(), 0);
+}
+
+#[test]
+pub fn test_invalid_margolus_rules() {
+    assert!(MargolusRule::new(&[0; 15]).is_none());
+    assert!(MargolusRule::new(&[16; 16]).is_none());
+    assert!(!MargolusRule::new(&[0; 16]).unwrap().is_reversible());
+    assert!(MargolusRule::critters().is_reversible());
+
+    let mut odd_universe = Universe::new(5, 4);
+    assert_eq!(odd_universe.set_margolus_preset(MargolusPreset::Tron),
+               Err(UniverseError::OddDimension { height: 5, width: 4 }));
+
+    let mut universe = Universe::new(4, 4);
+    assert_eq!(universe.set_margolus_rule(&[3; 10]),
+               Err(UniverseError::InvalidMargolusTable));
+
+    // The Game of Life cannot go back in time.
+    assert_eq!(universe.reverse_tick(), Err(UniverseError::IrreversibleRule));
+    universe.set_margolus_rule(&[0; 16]).unwrap();
+    assert_eq!(universe.reverse_tick(), Err(UniverseError::IrreversibleRule));
+    assert_eq!(universe.get_cells(), &[Cell::Dead; 16][..]);
+}
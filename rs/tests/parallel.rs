@@ -0,0 +1,47 @@
+//! Tests for the computation of ticks on multiple threads.
+
+#![cfg(feature = "parallel")]
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use wasm_game_of_life::Topology;
+use common::random_soup;
+
+#[test]
+pub fn test_same_result_than_single_threaded() {
+    // Use dimensions which cannot be evenly split into bands.
+    for &(height, width) in [(131, 77), (3, 200), (1, 1)].iter() {
+        let mut parallel = random_soup(height, width, height + width);
+        parallel.set_parallel(true);
+        let mut single_threaded = random_soup(height, width, height + width);
+        for _ in 0..100 {
+            parallel.tick();
+            single_threaded.tick();
+            assert_eq!(parallel.get_cells(), single_threaded.get_cells());
+            assert_eq!(parallel.changed_cells_len(),
+                       single_threaded.changed_cells_len());
+        }
+
+        // Switching back to a single thread keeps the same result.
+        parallel.set_parallel(false);
+        parallel.tick();
+        single_threaded.tick();
+        assert_eq!(parallel.get_cells(), single_threaded.get_cells());
+    }
+}
+
+#[test]
+pub fn test_bounded_topology() {
+    let mut parallel = random_soup(61, 45, 3);
+    parallel.set_parallel(true);
+    parallel.set_topology(Topology::Bounded);
+    let mut single_threaded = random_soup(61, 45, 3);
+    single_threaded.set_topology(Topology::Bounded);
+    for _ in 0..50 {
+        parallel.tick();
+        single_threaded.tick();
+        assert_eq!(parallel.get_cells(), single_threaded.get_cells());
+    }
+}
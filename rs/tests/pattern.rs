@@ -0,0 +1,147 @@
+//! Tests for the reading and writing of patterns.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Cell, Pattern, PatternError, Universe};
+
+const GLIDER_RLE: &str = "\
+#N Glider
+#C The smallest spaceship.
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+const GLIDER_PLAINTEXT: &str = "\
+!Name: Glider
+.O.
+..O
+OOO
+";
+
+/// Returns the row and column of every live cell of a pattern.
+fn live_cells(pattern: &Pattern) -> Vec<(u32, u32)> {
+    let mut cells = Vec::new();
+    for row in 0..pattern.height() {
+        for col in 0..pattern.width() {
+            if pattern.get(row, col) == Cell::Alive {
+                cells.push((row, col));
+            }
+        }
+    }
+    cells
+}
+
+#[test]
+pub fn test_read_rle() {
+    let pattern = Pattern::from_rle(GLIDER_RLE).unwrap();
+    assert_eq!(pattern.height(), 3);
+    assert_eq!(pattern.width(), 3);
+    assert_eq!(pattern.rule(), Some("B3/S23".to_owned()));
+    assert_eq!(live_cells(&pattern), vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(pattern.population(), 5);
+}
+
+#[test]
+pub fn test_read_rle_runs_across_lines() {
+    let pattern = Pattern::from_rle("x = 12, y = 4\n2o8b\n2o$\n\n2$11o!ignored").unwrap();
+    let mut expected = vec![(0, 0), (0, 1), (0, 10), (0, 11)];
+    expected.extend((0..11).map(|col| (3, col)));
+    assert_eq!(live_cells(&pattern), expected);
+    assert_eq!(pattern.rule(), None);
+}
+
+#[test]
+pub fn test_read_plaintext() {
+    let pattern = Pattern::from_plaintext(GLIDER_PLAINTEXT).unwrap();
+    assert_eq!((pattern.height(), pattern.width()), (3, 3));
+    assert_eq!(live_cells(&pattern), vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+    // Short and empty rows are completed with dead cells.
+    let pattern = Pattern::from_plaintext("*\n\n..*.\n\n").unwrap();
+    assert_eq!((pattern.height(), pattern.width()), (3, 4));
+    assert_eq!(live_cells(&pattern), vec![(0, 0), (2, 2)]);
+}
+
+#[test]
+pub fn test_parse_detects_format() {
+    let from_rle = Pattern::parse(GLIDER_RLE).unwrap();
+    let from_plaintext = Pattern::parse(GLIDER_PLAINTEXT).unwrap();
+    assert_eq!(from_rle.cells(), from_plaintext.cells());
+}
+
+#[test]
+pub fn test_write_rle() {
+    let pattern = Pattern::from_plaintext(GLIDER_PLAINTEXT).unwrap();
+    assert_eq!(pattern.to_rle(), "x = 3, y = 3\nbo$2bo$3o!\n");
+
+    // Empty rows are merged and the ones at the end are omitted.
+    let mut pattern = Pattern::new(6, 4).unwrap();
+    pattern.set(0, 3, Cell::Alive);
+    pattern.set(3, 0, Cell::Alive);
+    pattern.set(3, 1, Cell::Alive);
+    pattern.set_rule(Some("B3/S23".to_owned()));
+    assert_eq!(pattern.to_rle(), "x = 4, y = 6, rule = B3/S23\n3bo3$2o!\n");
+}
+
+#[test]
+pub fn test_write_rle_wraps_lines() {
+    let mut pattern = Pattern::new(1, 200).unwrap();
+    for col in (0..200).step_by(2) {
+        pattern.set(0, col, Cell::Alive);
+    }
+    let rle = pattern.to_rle();
+    assert!(rle.lines().all(|line| line.len() <= 70));
+    assert_eq!(Pattern::from_rle(&rle).unwrap(), pattern);
+}
+
+#[test]
+pub fn test_write_plaintext() {
+    let pattern = Pattern::from_rle(GLIDER_RLE).unwrap();
+    assert_eq!(pattern.to_plaintext(), ".O.\n..O\nOOO\n");
+}
+
+#[test]
+pub fn test_round_trips() {
+    let mut universe = Universe::new(17, 23);
+    universe.make_pulsar(2, 3);
+    universe.make_glider(10, 15);
+    let pattern = universe.to_pattern();
+    assert_eq!(pattern.population(), universe.population());
+
+    let from_rle = Pattern::from_rle(&pattern.to_rle()).unwrap();
+    assert_eq!(from_rle, pattern);
+    let from_plaintext = Pattern::from_plaintext(&pattern.to_plaintext()).unwrap();
+    assert_eq!(from_plaintext, pattern);
+    assert_eq!(Universe::from_pattern(&from_rle).unwrap().get_cells(),
+               universe.get_cells());
+}
+
+#[test]
+pub fn test_paste_pattern() {
+    let glider = Pattern::from_rle(GLIDER_RLE).unwrap();
+    let mut universe = Universe::new(5, 5);
+    universe.paste_pattern(&glider, 2, 1).unwrap();
+    let mut expected = Universe::new(5, 5);
+    expected.set_cells(&[(2, 2), (3, 3), (4, 1), (4, 2), (4, 3)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    assert!(universe.paste_pattern(&glider, 3, 0).is_err());
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[test]
+pub fn test_pattern_errors() {
+    assert_eq!(Pattern::from_rle("#C nothing\n"), Err(PatternError::MissingHeader));
+    assert_eq!(Pattern::from_rle("bo$2bo$3o!"), Err(PatternError::MissingHeader));
+    assert_eq!(Pattern::from_rle("x = 3, y = three\n3o!"),
+               Err(PatternError::InvalidHeader { line: 1 }));
+    assert_eq!(Pattern::from_rle("x = 3, y = 3\n3o$\n3oz!"),
+               Err(PatternError::UnexpectedCharacter { line: 3, character: 'z' }));
+    assert_eq!(Pattern::from_rle("x = 3, y = 1\n4o!"),
+               Err(PatternError::OutOfBounds { line: 2 }));
+    assert_eq!(Pattern::from_rle("x = 3, y = 1\no$o!"),
+               Err(PatternError::OutOfBounds { line: 2 }));
+    assert_eq!(Pattern::from_plaintext(".O.\n.#."),
+               Err(PatternError::UnexpectedCharacter { line: 2, character: '#' }));
+    assert!(Universe::from_pattern(&Pattern::new(0, 3).unwrap()).is_err());
+}
@@ -0,0 +1,149 @@
+//! Tests for the writing and reading of PNG files.
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use wasm_game_of_life::{Framebuffer, PngError, Universe, UniverseError};
+use common::random_soup;
+
+/// Returns a Universe of the given dimensions whose live cells form a glider
+/// at the given row and column.
+fn glider_at(height: u32, width: u32, row: u32, column: u32) -> Universe {
+    let mut universe = Universe::new(height, width);
+    universe.set_cells(&[(row, column + 1), (row + 1, column + 2),
+                         (row + 2, column), (row + 2, column + 1), (row + 2, column + 2)]);
+    universe
+}
+
+/// Returns a copy of a PNG file whose header declares the given dimensions.
+fn with_dimensions(png: &[u8], height: u32, width: u32) -> Vec<u8> {
+    let mut png = png.to_vec();
+    png[16..20].copy_from_slice(&width.to_be_bytes());
+    png[20..24].copy_from_slice(&height.to_be_bytes());
+
+    // CRC-32 of the chunk's type and data, which follows them.
+    let mut crc = !0u32;
+    for &byte in &png[12..29] {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { crc >> 1 ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    png[29..33].copy_from_slice(&(!crc).to_be_bytes());
+    png
+}
+
+#[test]
+pub fn test_round_trip() {
+    let universe = random_soup(37, 53, 3);
+    let mut framebuffer = Framebuffer::new();
+    framebuffer.set_cell_size(1);
+    framebuffer.set_grid_lines(false);
+    let png = framebuffer.render_png(&universe).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    let decoded = Universe::from_png(&png, 128).unwrap();
+    assert_eq!((decoded.height(), decoded.width()), (37, 53));
+    assert_eq!(decoded.get_cells(), universe.get_cells());
+}
+
+#[test]
+pub fn test_encoded_image() {
+    // The image has the dimensions of the rendered framebuffer, and its
+    // pixels are those of the live cells of the Universe.
+    let universe = glider_at(5, 6, 1, 2);
+    let mut framebuffer = Framebuffer::new();
+    framebuffer.set_cell_size(3);
+    framebuffer.set_alive_color(0x1020_30FF);
+    framebuffer.set_dead_color(0xF0F0_F080);
+    let png = framebuffer.render_png(&universe).unwrap();
+    let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+    let height = u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+    assert_eq!((width, height), (framebuffer.width(), framebuffer.height()));
+
+    // Dark cells are alive, the light grid and the transparent dead cells
+    // are not.
+    let decoded = Universe::from_png(&png, 100).unwrap();
+    let mut expected = Universe::new(height, width);
+    let mut cells = Vec::new();
+    for (row, col) in [(1, 3), (2, 4), (3, 2), (3, 3), (3, 4)].iter() {
+        for dy in 0..3 {
+            for dx in 0..3 {
+                cells.push((1 + row * 4 + dy, 1 + col * 4 + dx));
+            }
+        }
+    }
+    expected.set_cells(&cells);
+    assert_eq!(decoded.get_cells(), expected.get_cells());
+}
+
+#[test]
+pub fn test_decode_formats() {
+    let cases: [(&[u8], Universe); 4] = [
+        (include_bytes!("data/glider_gray1.png"), glider_at(5, 10, 1, 3)),
+        (include_bytes!("data/glider_gray8_stored.png"), glider_at(3, 3, 0, 0)),
+        (include_bytes!("data/glider_rgba_filters.png"), {
+            let mut universe = glider_at(6, 7, 2, 2);
+            universe.set_cells(&[(5, 6)]);
+            universe
+        }),
+        (include_bytes!("data/noisy_rgb16.png"), {
+            let mut universe = Universe::new(48, 64);
+            let cells: Vec<(u32, u32)> = (0..48)
+                .flat_map(|row| (0..64).map(move |col| (row, col)))
+                .filter(|(row, col)| (row * 7 + col * 3) % 5 == 0)
+                .collect();
+            universe.set_cells(&cells);
+            universe
+        }),
+    ];
+    for (png, expected) in cases.iter() {
+        let decoded = Universe::from_png(png, 128).unwrap();
+        assert_eq!((decoded.height(), decoded.width()),
+                   (expected.height(), expected.width()));
+        assert_eq!(decoded.get_cells(), expected.get_cells());
+    }
+}
+
+#[test]
+pub fn test_threshold() {
+    // The dark red pixel has a luminance of 35.
+    let png = include_bytes!("data/glider_rgba_filters.png");
+    let decoded = Universe::from_png(png, 36).unwrap();
+    assert_eq!(decoded.get_cell(5, 6), Ok(wasm_game_of_life::Cell::Alive));
+    let decoded = Universe::from_png(png, 35).unwrap();
+    assert_eq!(decoded.get_cell(5, 6), Ok(wasm_game_of_life::Cell::Dead));
+    assert_eq!(decoded.get_cells(), glider_at(6, 7, 2, 2).get_cells());
+}
+
+#[test]
+pub fn test_decode_errors() {
+    let mut framebuffer = Framebuffer::new();
+    let png = framebuffer.render_png(&random_soup(8, 8, 1)).unwrap();
+
+    assert_eq!(Universe::from_png(b"GIF89a", 128).err(), Some(PngError::InvalidSignature));
+    assert_eq!(Universe::from_png(&png[..png.len() - 5], 128).err(),
+               Some(PngError::Truncated));
+
+    let mut corrupted = png.clone();
+    corrupted[60] ^= 0xFF;
+    assert!(matches!(Universe::from_png(&corrupted, 128).err(),
+                     Some(PngError::CorruptChunk { .. })));
+
+    assert_eq!(Universe::from_png(include_bytes!("data/interlaced.png"), 128).err(),
+               Some(PngError::Interlaced));
+
+    // Huge dimensions are rejected before the pixels are decoded.
+    let huge = with_dimensions(&png, 5000, 4000);
+    assert_eq!(Universe::from_png(&huge, 128).err(),
+               Some(PngError::Universe(UniverseError::ImageTooLarge {
+                   height: 5000,
+                   width: 4000,
+               })));
+    let height = u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+    let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+    assert_eq!(with_dimensions(&png, height, width), png);
+    assert_eq!(PngError::Universe(UniverseError::NoLiveCell).to_string(),
+               UniverseError::NoLiveCell.to_string());
+}
@@ -0,0 +1,106 @@
+//! Tests for the copy, paste and transformation of regions of Universes.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Cell, PasteMode, Pattern, Universe, UniverseError};
+
+/// Returns the cells of the Universe in the plaintext format.
+fn plaintext(universe: &Universe) -> String {
+    universe.to_pattern().to_plaintext()
+}
+
+/// Create a Universe from a pattern in the plaintext format.
+fn universe(text: &str) -> Universe {
+    Universe::from_pattern(&Pattern::from_plaintext(text).unwrap()).unwrap()
+}
+
+#[test]
+pub fn test_copy_and_paste() {
+    let mut universe = universe(".....\n.OO..\n..O..\n.....\n");
+    let clipboard = universe.copy_region(1, 1, 2, 2).unwrap();
+    assert_eq!(clipboard.to_rle(), "x = 2, y = 2\n2o$bo!\n");
+
+    // The clipboard can be exchanged as text.
+    let clipboard = Pattern::parse(&clipboard.to_rle()).unwrap();
+    universe.paste(&clipboard, 2, 3, PasteMode::Overwrite).unwrap();
+    assert_eq!(plaintext(&universe), ".....\n.OO..\n..OOO\n....O\n");
+
+    assert_eq!(universe.copy_region(2, 4, 3, 1).err(),
+               Some(UniverseError::OutOfBounds { row: 4, column: 4, height: 4, width: 5 }));
+    assert_eq!(universe.paste(&clipboard, 3, 0, PasteMode::Or).err(),
+               Some(UniverseError::OutOfBounds { row: 4, column: 1, height: 4, width: 5 }));
+    assert_eq!(universe.copy_region(9, 9, 0, 3).unwrap().height(), 0);
+}
+
+#[test]
+pub fn test_paste_modes() {
+    let pattern = Pattern::from_plaintext("OO\n..\n").unwrap();
+    let paste = |mode: PasteMode| {
+        let mut universe = universe("O.\nO.\n");
+        universe.paste(&pattern, 0, 0, mode).unwrap();
+        plaintext(&universe)
+    };
+    assert_eq!(paste(PasteMode::Overwrite), "OO\n..\n");
+    assert_eq!(paste(PasteMode::Or), "OO\nO.\n");
+    assert_eq!(paste(PasteMode::Xor), ".O\nO.\n");
+    assert_eq!(paste(PasteMode::And), "O.\n..\n");
+}
+
+#[test]
+pub fn test_rotate() {
+    let mut universe = universe("OOO.\nO...\n....\n....\n");
+    universe.rotate_region_clockwise(0, 0, 2, 3).unwrap();
+    assert_eq!(plaintext(&universe), "OO..\n.O..\n.O..\n....\n");
+    universe.rotate_region_counterclockwise(0, 0, 3, 2).unwrap();
+    assert_eq!(plaintext(&universe), "OOO.\nO...\n....\n....\n");
+
+    // The turned region must fit in the Universe too.
+    assert_eq!(universe.rotate_region_clockwise(2, 0, 1, 4).err(),
+               Some(UniverseError::OutOfBounds { row: 5, column: 0, height: 4, width: 4 }));
+    assert_eq!(plaintext(&universe), "OOO.\nO...\n....\n....\n");
+
+    let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!\n").unwrap();
+    let turned = glider.rotate_clockwise().rotate_clockwise();
+    assert_eq!(turned.to_plaintext(), "OOO\nO..\n.O.\n");
+    assert_eq!(turned, glider.flip_horizontally().flip_vertically());
+    assert_eq!(glider.rotate_counterclockwise().rotate_clockwise(), glider);
+}
+
+#[test]
+pub fn test_flip() {
+    let mut universe = universe("OO..\n.O..\n....\n");
+    universe.flip_region_horizontally(0, 0, 2, 3).unwrap();
+    assert_eq!(plaintext(&universe), ".OO.\n.O..\n....\n");
+    universe.flip_region_vertically(0, 1, 3, 2).unwrap();
+    assert_eq!(plaintext(&universe), "....\n.O..\n.OO.\n");
+    assert!(universe.flip_region_vertically(0, 3, 1, 2).is_err());
+}
+
+#[test]
+pub fn test_invert_clear_and_fill() {
+    let mut universe = universe("O...\n.O..\n....\n");
+    universe.invert_region(0, 0, 2, 2).unwrap();
+    assert_eq!(plaintext(&universe), ".O..\nO...\n....\n");
+    universe.fill_region(1, 1, 2, 3).unwrap();
+    assert_eq!(plaintext(&universe), ".O..\nOOOO\n.OOO\n");
+    universe.clear_region(0, 0, 2, 3).unwrap();
+    assert_eq!(plaintext(&universe), "....\n...O\n.OOO\n");
+    assert!(universe.fill_region(2, 2, 2, 1).is_err());
+    assert_eq!(universe.get_cell(2, 2), Ok(Cell::Alive));
+
+    // Inverting a pattern keeps its dimensions and rule.
+    let pattern = Pattern::from_rle("x = 3, y = 1, rule = B3/S23\nbo!\n").unwrap();
+    assert_eq!(pattern.invert().to_rle(), "x = 3, y = 1, rule = B3/S23\nobo!\n");
+}
+
+#[test]
+pub fn test_transformed_regions_evolve() {
+    // A glider flipped in place travels the other way.
+    let mut universe = Universe::new(12, 12);
+    universe.make_glider(5, 5);
+    universe.flip_region_horizontally(4, 4, 3, 3).unwrap();
+    for _ in 0..4 {
+        universe.tick();
+    }
+    assert_eq!(universe.copy_region(5, 3, 3, 3).unwrap().to_plaintext(), ".O.\nO..\nOOO\n");
+}
@@ -0,0 +1,73 @@
+//! Tests for resizing and cropping a Universe.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Anchor, Cell, MargolusPreset, Universe, UniverseError};
+
+/// Returns the coordinates of every live cell in the Universe.
+fn live_cells(universe: &Universe) -> Vec<(u32, u32)> {
+    let width = universe.width();
+    universe.get_cells()
+        .iter()
+        .enumerate()
+        .filter(|&(_, &cell)| cell == Cell::Alive)
+        .map(|(idx, _)| (idx as u32 / width, idx as u32 % width))
+        .collect()
+}
+
+#[test]
+pub fn test_resize_anchors() {
+    let mut universe = Universe::new(4, 4);
+    universe.set_cells(&[(0, 0), (3, 3)]);
+
+    let mut grown = Universe::new(4, 4);
+    grown.set_cells(&[(0, 0), (3, 3)]);
+    grown.resize(8, 6, Anchor::Center).unwrap();
+    assert_eq!((grown.height(), grown.width()), (8, 6));
+    assert_eq!(live_cells(&grown), vec![(2, 1), (5, 4)]);
+
+    let mut grown = Universe::new(4, 4);
+    grown.set_cells(&[(0, 0), (3, 3)]);
+    grown.resize(6, 6, Anchor::BottomRight).unwrap();
+    assert_eq!(live_cells(&grown), vec![(2, 2), (5, 5)]);
+
+    // Cells outside of the new boundaries are lost.
+    universe.resize(2, 3, Anchor::TopLeft).unwrap();
+    assert_eq!(live_cells(&universe), vec![(0, 0)]);
+    universe.resize(2, 3, Anchor::Right).unwrap();
+    assert_eq!(live_cells(&universe), vec![(0, 0)]);
+}
+
+#[test]
+pub fn test_crop_and_expand() {
+    let mut universe = Universe::new(20, 20);
+    universe.make_glider(10, 5);
+    universe.crop_to_bounding_box(0).unwrap();
+    assert_eq!((universe.height(), universe.width()), (3, 3));
+    assert_eq!(live_cells(&universe), vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+    universe.expand(2).unwrap();
+    assert_eq!((universe.height(), universe.width()), (7, 7));
+    assert_eq!(live_cells(&universe), vec![(2, 3), (3, 4), (4, 2), (4, 3), (4, 4)]);
+
+    universe.crop_to_bounding_box(1).unwrap();
+    assert_eq!((universe.height(), universe.width()), (5, 5));
+    assert_eq!(live_cells(&universe), vec![(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+
+    // The glider keeps gliding in its new Universe.
+    universe.tick();
+    assert_eq!(live_cells(&universe).len(), 5);
+}
+
+#[test]
+pub fn test_invalid_resizes() {
+    let mut universe = Universe::new(4, 4);
+    assert_eq!(universe.crop_to_bounding_box(2), Err(UniverseError::NoLiveCell));
+    assert_eq!(universe.resize(0, 3, Anchor::Center),
+               Err(UniverseError::EmptyDimension { height: 0, width: 3 }));
+
+    universe.set_margolus_preset(MargolusPreset::Tron).unwrap();
+    assert_eq!(universe.resize(5, 4, Anchor::Center),
+               Err(UniverseError::OddDimension { height: 5, width: 4 }));
+    assert_eq!((universe.height(), universe.width()), (4, 4));
+}
@@ -0,0 +1,109 @@
+//! Tests for the serialization of Universes with `serde`.
+
+#![cfg(feature = "serde")]
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Cell, MargolusPreset, Topology, Universe};
+
+/// Create a Universe filled with a pseudo-random soup.
+fn soup(height: u32, width: u32) -> Universe {
+    let mut universe = Universe::new(height, width);
+    let mut state = 5u32;
+    let mut cells = Vec::new();
+    for row in 0..height {
+        for col in 0..width {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            if (state >> 16) & 3 == 0 {
+                cells.push((row, col));
+            }
+        }
+    }
+    universe.set_cells(&cells);
+    universe
+}
+
+#[test]
+pub fn test_round_trip() {
+    let mut universe = soup(30, 43);
+    universe.set_topology(Topology::Bounded);
+    universe.set_seed(7);
+    universe.set_birth_probability(0.9);
+    universe.set_noise_rate(0.001);
+    universe.tick();
+    universe.tick();
+
+    let json = serde_json::to_string(&universe).unwrap();
+    let mut restored: Universe = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.topology(), Topology::Bounded);
+    for _ in 0..20 {
+        assert_eq!(restored.generation(), universe.generation());
+        assert_eq!(restored.get_cells(), universe.get_cells());
+        universe.tick();
+        restored.tick();
+    }
+
+    let mut universe = soup(8, 8);
+    universe.set_margolus_preset(MargolusPreset::Tron).unwrap();
+    let json = serde_json::to_value(&universe).unwrap();
+    assert_eq!(json["margolus_rule"], serde_json::json!([15, 1, 2, 3, 4, 5, 6, 7,
+                                                         8, 9, 10, 11, 12, 13, 14, 0]));
+    let mut restored: Universe = serde_json::from_value(json).unwrap();
+    universe.tick();
+    restored.tick();
+    assert_eq!(restored.get_cells(), universe.get_cells());
+}
+
+#[test]
+pub fn test_compact_cells() {
+    let mut universe = Universe::new(4, 5);
+    universe.set_cells(&[(0, 0), (0, 2), (3, 4)]);
+    let json = serde_json::to_value(&universe).unwrap();
+    // Bits 0 and 2, then bit 19 in the third byte.
+    assert_eq!(json["cells"], "BQAI");
+    assert_eq!(json["rng_state"], "0000000000000000");
+
+    assert_eq!(serde_json::to_value(Universe::new(3, 3)).unwrap()["cells"], "");
+    let json = serde_json::to_string(&soup(1000, 1000)).unwrap();
+    assert!(json.len() < 170_000);
+}
+
+#[test]
+pub fn test_defaults() {
+    let universe: Universe = serde_json::from_str(r#"{"height": 3, "width": 4}"#).unwrap();
+    assert_eq!((universe.height(), universe.width()), (3, 4));
+    assert_eq!(universe.topology(), Topology::Torus);
+    assert_eq!(universe.generation(), 0);
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+
+    let universe: Universe = serde_json::from_str(
+        r#"{"height": 2, "width": 3, "generation": 4, "cells": "Iw"}"#).unwrap();
+    assert_eq!(universe.generation(), 4);
+    assert_eq!(universe.get_cells(), [Cell::Alive, Cell::Alive, Cell::Dead,
+                                      Cell::Dead, Cell::Dead, Cell::Alive]);
+}
+
+#[test]
+pub fn test_errors() {
+    let parse = |json: &str| serde_json::from_str::<Universe>(json).err().unwrap().to_string();
+    assert!(parse(r#"{"height": 0, "width": 4}"#).contains("empty"));
+    assert!(parse(r#"{"height": 2, "width": 2, "cells": "A"}"#).contains("invalid cells"));
+    assert!(parse(r#"{"height": 2, "width": 2, "cells": "AAAA"}"#).contains("invalid cells"));
+    assert!(parse(r#"{"height": 2, "width": 2, "cells": "D!=="}"#).contains("invalid cells"));
+    assert!(parse(r#"{"height": 2, "width": 2, "noise_rate": 2}"#).contains("probabilities"));
+    assert!(parse(r#"{"height": 2, "width": 2, "rng_state": "xyz"}"#).contains("generator"));
+    assert!(parse(r#"{"height": 2, "width": 2, "topology": "Sphere"}"#).contains("variant"));
+    assert!(parse(r#"{"height": 2, "width": 2, "margolus_rule": [0, 0]}"#)
+            .contains("invalid Margolus table"));
+    assert!(parse(r#"{"height": 3, "width": 2, "margolus_rule": [0, 1, 2, 3, 4, 5, 6, 7,
+                                                                 8, 9, 10, 11, 12, 13, 14, 15]}"#)
+            .contains("even"));
+}
+
+#[test]
+pub fn test_settings() {
+    assert_eq!(serde_json::to_string(&Cell::Alive).unwrap(), "\"Alive\"");
+    assert_eq!(serde_json::to_string(&Topology::Bounded).unwrap(), "\"Bounded\"");
+    assert_eq!(serde_json::from_str::<MargolusPreset>("\"Critters\"").unwrap(),
+               MargolusPreset::Critters);
+}
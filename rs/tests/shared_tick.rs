@@ -0,0 +1,105 @@
+//! Tests for ticks computed by multiple workers sharing the Universe's memory.
+
+#![cfg(feature = "wasm-threads")]
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use std::thread;
+use wasm_game_of_life::{run_shared_tick_bands, Anchor, MargolusPreset};
+use common::random_soup;
+
+#[test]
+pub fn test_shared_tick_with_workers() {
+    let mut shared = random_soup(97, 64, 5);
+    let mut single_threaded = random_soup(97, 64, 5);
+    for _ in 0..50 {
+        let job = shared.start_shared_tick(8);
+        assert_ne!(job, 0);
+        let workers: Vec<_> = (0..3)
+            .map(|_| thread::spawn(move || run_shared_tick_bands(job)))
+            .collect();
+        shared.finish_shared_tick();
+        assert!(shared.is_shared_tick_done());
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        single_threaded.tick();
+        assert_eq!(shared.get_cells(), single_threaded.get_cells());
+        assert_eq!(shared.changed_cells_len(), single_threaded.changed_cells_len());
+        assert_eq!(shared.generation(), single_threaded.generation());
+    }
+}
+
+#[test]
+pub fn test_shared_tick_without_workers() {
+    let mut shared = random_soup(20, 34, 9);
+    let mut single_threaded = random_soup(20, 34, 9);
+    for nb_bands in 0..40 {
+        shared.start_shared_tick(nb_bands);
+        shared.finish_shared_tick();
+        single_threaded.tick();
+        assert_eq!(shared.get_cells(), single_threaded.get_cells());
+    }
+
+    // Rules which cannot be split are directly computed.
+    shared.set_margolus_preset(MargolusPreset::Tron).unwrap();
+    single_threaded.set_margolus_preset(MargolusPreset::Tron).unwrap();
+    assert_eq!(shared.start_shared_tick(4), 0);
+    assert!(shared.is_shared_tick_done());
+    single_threaded.tick();
+    assert_eq!(shared.get_cells(), single_threaded.get_cells());
+}
+
+#[test]
+pub fn test_invalid_jobs() {
+    let mut shared = random_soup(30, 30, 3);
+    let mut single_threaded = random_soup(30, 30, 3);
+
+    // Addresses which are not the one of a pending tick are ignored.
+    run_shared_tick_bands(0);
+    run_shared_tick_bands(12_345);
+    let job = shared.start_shared_tick(4);
+    run_shared_tick_bands(job + 1);
+    shared.finish_shared_tick();
+    run_shared_tick_bands(job);
+    single_threaded.tick();
+    assert_eq!(shared.get_cells(), single_threaded.get_cells());
+}
+
+#[test]
+pub fn test_update_during_shared_tick() {
+    let mut shared = random_soup(40, 50, 7);
+    let mut single_threaded = random_soup(40, 50, 7);
+
+    // Pending ticks are finished before the Universe is updated.
+    let job = shared.start_shared_tick(4);
+    let worker = thread::spawn(move || run_shared_tick_bands(job));
+    shared.tick();
+    worker.join().unwrap();
+    single_threaded.tick();
+    single_threaded.tick();
+    assert_eq!(shared.get_cells(), single_threaded.get_cells());
+    assert_eq!(shared.generation(), 2);
+
+    shared.start_shared_tick(4);
+    shared.resize(20, 20, Anchor::Center).unwrap();
+    single_threaded.tick();
+    single_threaded.resize(20, 20, Anchor::Center).unwrap();
+    assert_eq!(shared.get_cells(), single_threaded.get_cells());
+
+    shared.start_shared_tick(4);
+    shared.toggle_cell(3, 3);
+    single_threaded.tick();
+    single_threaded.toggle_cell(3, 3);
+    assert_eq!(shared.get_cells(), single_threaded.get_cells());
+
+    // Dropping a Universe also finishes its pending tick.
+    let job = shared.start_shared_tick(4);
+    let worker = thread::spawn(move || run_shared_tick_bands(job));
+    drop(shared);
+    worker.join().unwrap();
+    run_shared_tick_bands(job);
+}
@@ -0,0 +1,46 @@
+//! Differential tests between the SIMD and the scalar computation of ticks.
+
+#![cfg(feature = "simd")]
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use wasm_game_of_life::{simd_compiled, Topology};
+use common::random_soup;
+
+#[test]
+pub fn test_same_result_than_scalar() {
+    #[cfg(target_arch = "x86_64")]
+    assert!(simd_compiled());
+
+    // Widths around the number of cells processed at once.
+    let widths = [1, 2, 3, 16, 17, 18, 19, 33, 34, 35, 64, 100];
+    for (i, &width) in widths.iter().enumerate() {
+        let seed = i as u32;
+        let mut simd = random_soup(23, width, seed);
+        simd.set_simd(true);
+        let mut scalar = random_soup(23, width, seed);
+        scalar.set_active_tiles_tracking(false);
+        for _ in 0..60 {
+            simd.tick();
+            scalar.tick();
+            assert_eq!(simd.get_cells(), scalar.get_cells(), "width: {}", width);
+            assert_eq!(simd.changed_cells_len(), scalar.changed_cells_len());
+        }
+    }
+}
+
+#[test]
+pub fn test_bounded_topology() {
+    let mut simd = random_soup(29, 50, 7);
+    simd.set_simd(true);
+    simd.set_topology(Topology::Bounded);
+    let mut scalar = random_soup(29, 50, 7);
+    scalar.set_topology(Topology::Bounded);
+    for _ in 0..50 {
+        simd.tick();
+        scalar.tick();
+        assert_eq!(simd.get_cells(), scalar.get_cells());
+    }
+}
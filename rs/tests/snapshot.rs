@@ -0,0 +1,152 @@
+//! Tests for the saving and restoring of Universes through snapshots.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{MargolusPreset, SnapshotError, Topology, Universe, UniverseError};
+
+/// Create a Universe filled with a pseudo-random soup of the given density,
+/// in percents.
+fn soup(height: u32, width: u32, density: u32) -> Universe {
+    let mut universe = Universe::new(height, width);
+    let mut state = 11u32;
+    let mut cells = Vec::new();
+    for row in 0..height {
+        for col in 0..width {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            if (state >> 16) % 100 < density {
+                cells.push((row, col));
+            }
+        }
+    }
+    universe.set_cells(&cells);
+    universe
+}
+
+/// Returns the CRC-32 of the given bytes.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Replace the checksum at the end of a snapshot by the one of its content.
+fn fix_checksum(snapshot: &mut [u8]) {
+    let len = snapshot.len();
+    let crc = crc32(&snapshot[..len - 4]);
+    snapshot[len - 4..].copy_from_slice(&crc.to_le_bytes());
+}
+
+/// Compute the given number of generations of both Universes, checking that
+/// they stay the same.
+fn assert_same_evolution(first: &mut Universe, second: &mut Universe, generations: u32) {
+    for _ in 0..=generations {
+        assert_eq!(first.generation(), second.generation());
+        assert_eq!(first.get_cells(), second.get_cells());
+        first.tick();
+        second.tick();
+    }
+}
+
+#[test]
+pub fn test_round_trip() {
+    let mut universe = soup(40, 50, 30);
+    universe.set_topology(Topology::Bounded);
+    for _ in 0..7 {
+        universe.tick();
+    }
+    let mut restored = Universe::from_snapshot(&universe.to_snapshot()).unwrap();
+    assert_eq!((restored.height(), restored.width()), (40, 50));
+    assert_eq!(restored.topology(), Topology::Bounded);
+    assert_eq!(restored.generation(), 7);
+    assert_same_evolution(&mut universe, &mut restored, 20);
+}
+
+#[test]
+pub fn test_stochastic_round_trip() {
+    // The random number generator continues from where it was.
+    let mut universe = soup(32, 32, 40);
+    universe.set_seed(42);
+    universe.set_birth_probability(0.75);
+    universe.set_survival_probability(0.5);
+    universe.set_noise_rate(0.01);
+    universe.tick();
+    let mut restored = Universe::from_snapshot(&universe.to_snapshot()).unwrap();
+    assert_same_evolution(&mut universe, &mut restored, 20);
+}
+
+#[test]
+pub fn test_margolus_round_trip() {
+    let mut universe = soup(24, 24, 20);
+    universe.set_margolus_preset(MargolusPreset::Critters).unwrap();
+    universe.tick();
+    let mut restored = Universe::from_snapshot(&universe.to_snapshot()).unwrap();
+    assert_same_evolution(&mut universe, &mut restored, 10);
+    assert!(restored.reverse_tick().is_ok());
+}
+
+#[test]
+pub fn test_compact_cells() {
+    // Sparse Universes are written as runs, dense ones as bits.
+    let mut universe = Universe::new(1000, 1000);
+    universe.make_glider(500, 500);
+    assert!(universe.to_snapshot().len() < 120);
+    let restored = Universe::from_snapshot(&universe.to_snapshot()).unwrap();
+    assert_eq!(restored.get_cells(), universe.get_cells());
+
+    let universe = soup(100, 80, 50);
+    assert_eq!(universe.to_snapshot().len(), 77 + 100 * 80 / 8 + 4);
+}
+
+#[test]
+pub fn test_errors() {
+    let snapshot = soup(10, 12, 30).to_snapshot();
+    assert_eq!(Universe::from_snapshot(b"x = 3, y = 3").err(), Some(SnapshotError::InvalidSignature));
+    for len in 0..snapshot.len() {
+        assert_eq!(Universe::from_snapshot(&snapshot[..len]).err(), Some(SnapshotError::Truncated));
+    }
+
+    let mut corrupted = snapshot.clone();
+    corrupted[80] ^= 0x10;
+    assert_eq!(Universe::from_snapshot(&corrupted).err(), Some(SnapshotError::CorruptData));
+    let mut extended = snapshot.clone();
+    extended.push(0);
+    assert_eq!(Universe::from_snapshot(&extended).err(), Some(SnapshotError::CorruptData));
+
+    let mut newer = snapshot.clone();
+    newer[8] = 2;
+    assert_eq!(Universe::from_snapshot(&newer).err(),
+               Some(SnapshotError::UnsupportedVersion { version: 2 }));
+
+    // Invalid topology.
+    let mut invalid = snapshot.clone();
+    invalid[18] = 2;
+    fix_checksum(&mut invalid);
+    assert_eq!(Universe::from_snapshot(&invalid).err(), Some(SnapshotError::InvalidData));
+
+    // Margolus rule on odd dimensions.
+    let mut odd = Universe::new(3, 4).to_snapshot();
+    odd[23] = 1;
+    fix_checksum(&mut odd);
+    assert_eq!(Universe::from_snapshot(&odd).err(),
+               Some(SnapshotError::Universe(UniverseError::OddDimension { height: 3, width: 4 })));
+
+    // Dimensions which do not match the cells, checked before allocating them.
+    let mut huge = snapshot.clone();
+    huge[10..14].copy_from_slice(&60_000u32.to_le_bytes());
+    huge[14..18].copy_from_slice(&60_000u32.to_le_bytes());
+    fix_checksum(&mut huge);
+    assert_eq!(Universe::from_snapshot(&huge).err(), Some(SnapshotError::InvalidData));
+    let mut empty = Universe::new(100, 100).to_snapshot();
+    empty[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+    fix_checksum(&mut empty);
+    assert_eq!(Universe::from_snapshot(&empty).err(),
+               Some(SnapshotError::Universe(UniverseError::TooLarge {
+                   height: u32::MAX,
+                   width: 100,
+               })));
+}
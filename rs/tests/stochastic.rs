@@ -0,0 +1,75 @@
+//! Tests for the stochastic rules and the noise.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Cell, Universe};
+
+fn noisy_universe(seed: u32) -> Universe {
+    let mut universe = Universe::new(32, 32);
+    universe.make_pulsar(16, 16);
+    universe.set_seed(seed);
+    universe.set_birth_probability(0.8);
+    universe.set_survival_probability(0.9);
+    universe.set_noise_rate(0.01);
+    universe
+}
+
+#[test]
+pub fn test_same_seed_same_run() {
+    let mut first = noisy_universe(7);
+    let mut second = noisy_universe(7);
+    let mut other = noisy_universe(8);
+    for _ in 0..20 {
+        first.tick();
+        second.tick();
+        other.tick();
+    }
+    assert_eq!(first.get_cells(), second.get_cells());
+    assert_ne!(first.get_cells(), other.get_cells());
+
+    // Re-seeding starts the sequence again.
+    let mut reseeded = noisy_universe(1);
+    reseeded.set_seed(7);
+    for _ in 0..20 {
+        reseeded.tick();
+    }
+    assert_eq!(first.get_cells(), reseeded.get_cells());
+}
+
+#[test]
+pub fn test_transition_probabilities() {
+    // A blinker without births only keeps its center cell.
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    universe.set_birth_probability(0.);
+    universe.tick();
+    let mut expected = Universe::new(5, 5);
+    expected.set_cells(&[(2, 2)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    // Without survivals, only newly-born cells are alive.
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    universe.set_survival_probability(0.);
+    universe.tick();
+    let mut expected = Universe::new(5, 5);
+    expected.set_cells(&[(2, 1), (2, 3)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[test]
+pub fn test_noise_rate() {
+    let mut universe = Universe::new(10, 10);
+    universe.set_noise_rate(1.);
+    universe.tick();
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Alive));
+
+    let mut universe = Universe::new(100, 100);
+    universe.set_noise_rate(0.1);
+    universe.tick();
+    let alive = universe.get_cells()
+        .iter()
+        .filter(|&&cell| cell == Cell::Alive)
+        .count();
+    assert!(alive > 800 && alive < 1200, "{} cells alive", alive);
+}
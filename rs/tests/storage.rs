@@ -0,0 +1,68 @@
+//! Tests for the storage of the cells, surrounded by a ghost border.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Cell, Universe};
+
+#[test]
+pub fn test_cells_ptr_with_stride() {
+    let mut universe = Universe::new(3, 5);
+    universe.set_cells(&[(0, 0), (1, 4), (2, 2)]);
+    assert_eq!(universe.stride(), 7);
+
+    let stride = universe.stride() as usize;
+    let cells = unsafe {
+        std::slice::from_raw_parts(universe.get_cells_ptr(), 2 * stride + 5)
+    };
+    for row in 0..3 {
+        for col in 0..5 {
+            assert_eq!(cells[row * stride + col],
+                       universe.get_cell(row as u32, col as u32).unwrap());
+        }
+    }
+    assert_eq!(universe.get_cells(), vec![
+        Cell::Alive, Cell::Dead, Cell::Dead, Cell::Dead, Cell::Dead,
+        Cell::Dead, Cell::Dead, Cell::Dead, Cell::Dead, Cell::Alive,
+        Cell::Dead, Cell::Dead, Cell::Alive, Cell::Dead, Cell::Dead,
+    ]);
+}
+
+#[test]
+pub fn test_wrapping_around_corners() {
+    // A block split between the four corners of the Universe is stable.
+    let mut universe = Universe::new(6, 7);
+    let corners = [(0, 0), (0, 6), (5, 0), (5, 6)];
+    universe.set_cells(&corners);
+    let initial_cells = universe.get_cells();
+    universe.tick();
+    assert_eq!(universe.get_cells(), initial_cells);
+    assert_eq!(universe.changed_cells_len(), 0);
+
+    // A blinker crossing the left and right edges oscillates across them.
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(2, 4), (2, 0), (2, 1)]);
+    universe.tick();
+    let mut expected = Universe::new(5, 5);
+    expected.set_cells(&[(1, 0), (2, 0), (3, 0)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[test]
+pub fn test_single_row_and_column() {
+    // With a single row, the rows up and down of a cell are its own one: the
+    // cells on its left and right are thus counted three times and the cell
+    // itself twice.
+    let mut universe = Universe::new(1, 5);
+    universe.set_cells(&[(0, 1), (0, 2)]);
+    universe.tick();
+    assert_eq!(universe.get_cells(), vec![
+        Cell::Alive, Cell::Dead, Cell::Dead, Cell::Alive, Cell::Dead,
+    ]);
+
+    let mut universe = Universe::new(5, 1);
+    universe.set_cells(&[(1, 0), (2, 0)]);
+    universe.tick();
+    assert_eq!(universe.get_cells(), vec![
+        Cell::Alive, Cell::Dead, Cell::Dead, Cell::Alive, Cell::Dead,
+    ]);
+}
@@ -0,0 +1,97 @@
+//! Tests for the rendering of Universes as SVG images.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{MargolusPreset, SvgRenderer, Universe};
+
+/// Returns the `rect` elements drawing live cells in the given image.
+fn cell_rects(svg: &str) -> Vec<&str> {
+    let cells = &svg[svg.find("<g").unwrap()..svg.find("</g>").unwrap()];
+    cells.lines().skip(1).collect()
+}
+
+#[test]
+pub fn test_merged_runs() {
+    let mut universe = Universe::new(6, 6);
+    // A block, a blinker and a run which is not repeated on the next row.
+    universe.set_cells(&[(0, 0), (0, 1), (1, 0), (1, 1),
+                         (3, 3), (3, 4), (3, 5),
+                         (4, 2), (4, 3), (4, 4), (4, 5), (5, 4)]);
+    let mut renderer = SvgRenderer::new();
+    renderer.set_metadata(false);
+    let svg = renderer.render(&universe);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" \
+                             width=\"60\" height=\"60\" viewBox=\"0 0 60 60\">\n\
+                             <rect width=\"60\" height=\"60\" fill=\"#ffffff\"/>\n\
+                             <g fill=\"#000000\">\n"));
+    assert!(svg.ends_with("</g>\n</svg>\n"));
+    assert_eq!(cell_rects(&svg), [
+        "<rect x=\"0\" y=\"0\" width=\"20\" height=\"20\"/>",
+        "<rect x=\"30\" y=\"30\" width=\"30\" height=\"10\"/>",
+        "<rect x=\"20\" y=\"40\" width=\"40\" height=\"10\"/>",
+        "<rect x=\"40\" y=\"50\" width=\"10\" height=\"10\"/>",
+    ]);
+}
+
+#[test]
+pub fn test_crop_and_grid() {
+    let mut universe = Universe::new(6, 6);
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3), (3, 5)]);
+    let mut renderer = SvgRenderer::new();
+    renderer.set_metadata(false);
+    renderer.set_cell_size(4);
+    renderer.set_grid_lines(true);
+    renderer.set_crop(2, 3, 2, 10);
+    let svg = renderer.render(&universe);
+    assert!(svg.contains("width=\"12\" height=\"8\""));
+    assert_eq!(cell_rects(&svg), [
+        "<rect x=\"0\" y=\"0\" width=\"4\" height=\"8\"/>",
+        "<rect x=\"8\" y=\"4\" width=\"4\" height=\"4\"/>",
+    ]);
+    assert!(svg.contains("<path d=\"M0 0H12M0 4H12M0 8H12M0 0V8M4 0V8M8 0V8M12 0V8\" \
+                          fill=\"none\" stroke=\"#cccccc\" stroke-width=\"1\"/>"));
+
+    // Regions outside of the Universe are empty images.
+    renderer.set_crop(6, 0, 2, 2);
+    let svg = renderer.render(&universe);
+    assert!(svg.contains("width=\"8\" height=\"0\""));
+    assert!(!svg.contains("<g"));
+    assert!(!svg.contains("<path"));
+}
+
+#[test]
+pub fn test_colors() {
+    let mut universe = Universe::new(2, 2);
+    universe.set_cells(&[(0, 0)]);
+    let mut renderer = SvgRenderer::new();
+    renderer.set_alive_color(0x3366_99CC);
+    renderer.set_dead_color(0xFFFF_FF00);
+    let svg = renderer.render(&universe);
+    assert!(svg.contains("<g fill=\"#336699\" fill-opacity=\"0.8\">"));
+    assert!(!svg.contains("<rect width"));
+}
+
+#[test]
+pub fn test_metadata() {
+    let mut universe = Universe::new(4, 6);
+    universe.set_cells(&[(1, 1), (1, 2), (1, 3)]);
+    universe.tick();
+    universe.tick();
+    let mut renderer = SvgRenderer::new();
+    let svg = renderer.render(&universe);
+    assert!(svg.contains("data-generation=\"2\" data-rule=\"B3/S23\" data-region=\"0 0 4 6\""));
+    assert!(svg.contains("<desc>Universe of 4x6 cells following the rule B3/S23, \
+                          at generation 2</desc>"));
+
+    renderer.set_crop(1, 2, 10, 3);
+    universe.set_margolus_preset(MargolusPreset::Critters).unwrap();
+    universe.set_noise_rate(0.25);
+    let svg = renderer.render(&universe);
+    assert!(svg.contains("data-rule=\"Critters (noise rate 0.25)\" data-region=\"1 2 3 3\""));
+    assert!(svg.contains(", rows 1 to 3 and columns 2 to 4</desc>"));
+
+    universe.remove_margolus_rule();
+    universe.set_birth_probability(0.5);
+    let svg = renderer.render(&universe);
+    assert!(svg.contains("data-rule=\"B3/S23 (birth probability 0.5, noise rate 0.25)\""));
+}
@@ -0,0 +1,66 @@
+//! Tests for the rendering of Universes in terminals.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{ColorMode, TerminalRenderer, Universe};
+
+#[test]
+pub fn test_monochrome_half_blocks() {
+    let mut universe = Universe::new(3, 4);
+    universe.set_cells(&[(0, 0), (1, 1), (0, 2), (1, 2), (2, 3)]);
+    let mut renderer = TerminalRenderer::new();
+    renderer.set_color_mode(ColorMode::Monochrome);
+    assert_eq!(renderer.rows_per_line(), 2);
+    assert_eq!(renderer.render(&universe, 0, 0, 2, 4), vec!["▀▄█ ", "   ▀"]);
+
+    // Parts outside of the Universe are blank.
+    assert_eq!(renderer.render(&universe, 1, 2, 3, 3), vec!["▀▄ ", "   ", "   "]);
+}
+
+#[test]
+pub fn test_monochrome_full_blocks() {
+    let mut universe = Universe::new(3, 3);
+    universe.set_cells(&[(0, 0), (1, 1), (2, 2)]);
+    let mut renderer = TerminalRenderer::new();
+    renderer.set_color_mode(ColorMode::Monochrome);
+    renderer.set_half_blocks(false);
+    assert_eq!(renderer.rows_per_line(), 1);
+    assert_eq!(renderer.render(&universe, 0, 0, 3, 3), vec!["█  ", " █ ", "  █"]);
+
+    // The cursor is shown in inverse video.
+    renderer.set_cursor(Some((1, 1)));
+    assert_eq!(renderer.render(&universe, 1, 0, 1, 3), vec![" \x1b[0;7m█\x1b[0m "]);
+}
+
+#[test]
+pub fn test_state_colors() {
+    let mut universe = Universe::new(2, 3);
+    universe.set_cells(&[(0, 0), (1, 0), (0, 1), (1, 2)]);
+    let renderer = TerminalRenderer::new();
+    assert_eq!(renderer.render(&universe, 0, 0, 1, 3),
+               vec!["\x1b[0;38;5;231m█▀▄\x1b[0m"]);
+
+    // Cells of different colours share a character.
+    let mut renderer = TerminalRenderer::new();
+    renderer.set_cursor(Some((1, 1)));
+    assert_eq!(renderer.render(&universe, 0, 0, 1, 3),
+               vec!["\x1b[0;38;5;231m█\x1b[0;38;5;231;48;5;53m▀\x1b[0;38;5;231m▄\x1b[0m"]);
+}
+
+#[test]
+pub fn test_age_colors() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2), (4, 4)]);
+    let mut renderer = TerminalRenderer::new();
+    renderer.set_color_mode(ColorMode::Age);
+    renderer.set_half_blocks(false);
+
+    // Without age tracking, cells are coloured by state.
+    assert_eq!(renderer.render(&universe, 1, 1, 1, 1), vec!["\x1b[0;38;5;231m█\x1b[0m"]);
+
+    universe.set_age_tracking(true);
+    for _ in 0..5 {
+        universe.tick();
+    }
+    assert_eq!(renderer.render(&universe, 1, 1, 1, 1), vec!["\x1b[0;38;5;214m█\x1b[0m"]);
+}
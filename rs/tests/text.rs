@@ -0,0 +1,61 @@
+//! Tests for the rendering of Universes as plain text.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{TextMode, TextRenderer, Universe};
+
+/// Create a Universe containing a glider in its top-left corner.
+fn glider_universe(height: u32, width: u32) -> Universe {
+    let mut universe = Universe::new(height, width);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    universe
+}
+
+#[test]
+pub fn test_default_glyphs() {
+    let universe = glider_universe(3, 4);
+    let text = TextRenderer::new().render(&universe);
+    assert_eq!(text, "◻◼◻◻\n◻◻◼◻\n◼◼◼◻\n");
+    assert_eq!(universe.to_string(), text);
+}
+
+#[test]
+pub fn test_custom_glyphs() {
+    let universe = glider_universe(3, 4);
+    assert_eq!(TextRenderer::plaintext().render(&universe), ".O..\n..O.\nOOO.\n");
+
+    let mut renderer = TextRenderer::new();
+    renderer.set_dead_char(' ');
+    renderer.set_alive_char('#');
+    assert_eq!(renderer.render(&universe), " #  \n  # \n### \n");
+}
+
+#[test]
+pub fn test_window() {
+    let universe = glider_universe(5, 5);
+    let mut renderer = TextRenderer::plaintext();
+    renderer.set_window(1, 1, 2, 3);
+    assert_eq!(renderer.render(&universe), ".O.\nOO.\n");
+
+    // Parts of the window outside of the Universe are ignored.
+    renderer.set_window(2, 3, 10, 10);
+    assert_eq!(renderer.render(&universe), "..\n..\n..\n");
+    renderer.set_window(5, 0, 3, 3);
+    assert_eq!(renderer.render(&universe), "");
+
+    renderer.clear_window();
+    assert_eq!(renderer.render(&universe).lines().count(), 5);
+}
+
+#[test]
+pub fn test_braille() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cells(&[(0, 0), (1, 0), (2, 0), (3, 0), (0, 1), (3, 1), (4, 4)]);
+    let mut renderer = TextRenderer::new();
+    renderer.set_mode(TextMode::Braille);
+    assert_eq!(renderer.render(&universe), "⣏⠀⠀\n⠀⠀⠁\n");
+
+    // Blocks crossing the edges of the window only show the cells within it.
+    renderer.set_window(0, 0, 2, 1);
+    assert_eq!(renderer.render(&universe), "⠃\n");
+}
@@ -0,0 +1,78 @@
+//! Tests for the connection of the Universe's edges.
+
+extern crate wasm_game_of_life;
+
+mod common;
+
+use wasm_game_of_life::{TickEngine, Topology, Universe};
+use common::random_soup;
+
+#[test]
+pub fn test_default_topology() {
+    assert_eq!(Universe::new(4, 4).topology(), Topology::Torus);
+}
+
+#[test]
+pub fn test_bounded_edges() {
+    // A blinker along the top edge cannot grow outside of the Universe.
+    let mut universe = Universe::new(5, 5);
+    universe.set_topology(Topology::Bounded);
+    universe.set_cells(&[(0, 1), (0, 2), (0, 3)]);
+    universe.tick();
+    let mut expected = Universe::new(5, 5);
+    expected.set_cells(&[(0, 2), (1, 2)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    // A block split between the corners is not one anymore.
+    let mut universe = Universe::new(6, 6);
+    universe.set_topology(Topology::Bounded);
+    universe.set_cells(&[(0, 0), (0, 5), (5, 0), (5, 5)]);
+    universe.tick();
+    assert_eq!(universe.get_cells(), Universe::new(6, 6).get_cells());
+
+    // Going back to a torus wraps around the edges again.
+    universe.set_topology(Topology::Torus);
+    universe.set_cells(&[(0, 0), (0, 5), (5, 0), (5, 5)]);
+    universe.tick();
+    assert_eq!(universe.changed_cells_len(), 0);
+}
+
+#[test]
+pub fn test_active_tiles_after_topology_change() {
+    // A block split between the top and bottom edges only lives on a torus.
+    let cells = [(0, 10), (0, 11), (63, 10), (63, 11)];
+    let mut tracked = Universe::new(64, 64);
+    let mut full_scan = Universe::new(64, 64);
+    full_scan.set_active_tiles_tracking(false);
+    for universe in [&mut tracked, &mut full_scan].iter_mut() {
+        universe.set_cells(&cells);
+        universe.tick();
+        universe.tick();
+        universe.set_topology(Topology::Bounded);
+        universe.tick();
+    }
+    assert_eq!(tracked.get_cells(), full_scan.get_cells());
+    assert_eq!(tracked.population(), 0);
+}
+
+#[test]
+pub fn test_bounded_engines_agree() {
+    for &(height, width) in [(1, 1), (1, 9), (9, 1), (37, 70)].iter() {
+        let seed = height * width;
+        let mut table = random_soup(height, width, seed);
+        table.set_tick_engine(TickEngine::LookupTable);
+        let mut tiles = random_soup(height, width, seed);
+        let mut counting = random_soup(height, width, seed);
+        counting.set_active_tiles_tracking(false);
+        for universe in [&mut table, &mut tiles, &mut counting].iter_mut() {
+            universe.set_topology(Topology::Bounded);
+        }
+        for _ in 0..50 {
+            table.tick();
+            tiles.tick();
+            counting.tick();
+            assert_eq!(table.get_cells(), counting.get_cells());
+            assert_eq!(tiles.get_cells(), counting.get_cells());
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Tests for rendering the visible part of a Universe through a viewport.
+
+extern crate wasm_game_of_life;
+
+use wasm_game_of_life::{Universe, Viewport};
+
+const ALIVE: [u8; 4] = [0x00, 0x00, 0x00, 0xFF];
+const DEAD: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+const GRID: [u8; 4] = [0xCC, 0xCC, 0xCC, 0xFF];
+const BACKGROUND: [u8; 4] = [0xEE, 0xEE, 0xEE, 0xFF];
+
+/// Returns the RGBA bytes of the pixel at the given coordinates.
+fn pixel(viewport: &Viewport, x: u32, y: u32) -> [u8; 4] {
+    let pixels = unsafe {
+        std::slice::from_raw_parts(viewport.pixels_ptr(), viewport.pixels_len() as usize)
+    };
+    let idx = ((y * viewport.width() + x) * 4) as usize;
+    [pixels[idx], pixels[idx + 1], pixels[idx + 2], pixels[idx + 3]]
+}
+
+#[test]
+pub fn test_render_visible_part() {
+    let mut universe = Universe::new(100, 1000);
+    universe.set_cells(&[(0, 0), (2, 3)]);
+    let mut viewport = Viewport::new(40, 30);
+    assert_eq!(viewport.pixels_len(), 40 * 30 * 4);
+    viewport.render(&universe);
+
+    // Cells of 8 pixels, beginning with a grid line.
+    assert_eq!(pixel(&viewport, 0, 0), GRID);
+    assert_eq!(pixel(&viewport, 1, 1), ALIVE);
+    assert_eq!(pixel(&viewport, 7, 7), ALIVE);
+    assert_eq!(pixel(&viewport, 8, 8), GRID);
+    assert_eq!(pixel(&viewport, 9, 9), DEAD);
+    assert_eq!(pixel(&viewport, 3 * 8 + 4, 2 * 8 + 4), ALIVE);
+
+    // Panning moves the cells along with the mouse.
+    viewport.pan(-16., -8.);
+    viewport.render(&universe);
+    assert_eq!(pixel(&viewport, 4, 4), DEAD);
+    assert_eq!(pixel(&viewport, 8 + 4, 8 + 4), ALIVE);
+
+    // The area outside the Universe has its own colour.
+    viewport.pan(16. + 24., 8. + 16.);
+    viewport.render(&universe);
+    assert_eq!(pixel(&viewport, 4, 4), BACKGROUND);
+    assert_eq!(pixel(&viewport, 3 * 8 + 4, 2 * 8 + 4), ALIVE);
+}
+
+#[test]
+pub fn test_grid_lines_with_fractional_origin() {
+    let mut universe = Universe::new(4, 4);
+    universe.set_cells(&[(0, 0), (1, 1)]);
+    let mut viewport = Viewport::new(16, 16);
+
+    // Cells of 8 pixels, whose first 3 columns and 2 rows are hidden: the grid
+    // lines of the first cells are out of the screen.
+    viewport.pan(-3., -2.);
+    viewport.render(&universe);
+    assert_eq!(pixel(&viewport, 0, 0), ALIVE);
+    assert_eq!(pixel(&viewport, 4, 4), ALIVE);
+    assert_eq!(pixel(&viewport, 5, 2), GRID);
+    assert_eq!(pixel(&viewport, 2, 6), GRID);
+    assert_eq!(pixel(&viewport, 6, 7), ALIVE);
+}
+
+#[test]
+pub fn test_zoom_out_shows_density() {
+    let mut universe = Universe::new(8, 8);
+    universe.set_cells(&[(0, 0), (0, 1), (1, 0), (1, 1), (2, 2), (3, 3)]);
+    let mut viewport = Viewport::new(4, 4);
+    viewport.zoom_at(0., 0., -4);
+    assert_eq!(viewport.zoom(), -1);
+    viewport.render(&universe);
+
+    // Each pixel shows 2x2 cells.
+    assert_eq!(pixel(&viewport, 0, 0), ALIVE);
+    assert_eq!(pixel(&viewport, 1, 1), [0x80, 0x80, 0x80, 0xFF]);
+    assert_eq!(pixel(&viewport, 1, 0), DEAD);
+    assert_eq!(pixel(&viewport, 3, 3), DEAD);
+
+    // Zoom levels are bounded.
+    viewport.set_zoom(-100);
+    assert_eq!(viewport.zoom(), -8);
+    viewport.set_zoom(100);
+    assert_eq!(viewport.zoom(), 6);
+}
+
+#[test]
+pub fn test_screen_to_cell_coordinates() {
+    let universe = Universe::new(20, 30);
+    let mut viewport = Viewport::new(100, 100);
+    assert_eq!(viewport.column_at(&universe, 17.), Some(2));
+    assert_eq!(viewport.row_at(&universe, 7.9), Some(0));
+    assert_eq!(viewport.column_at(&universe, 30. * 8.), None);
+    assert_eq!(viewport.row_at(&universe, -1.), None);
+
+    // Zooming keeps the cell under the mouse in place.
+    viewport.zoom_at(44., 20., 2);
+    assert_eq!(viewport.column_at(&universe, 44.), Some(5));
+    assert_eq!(viewport.row_at(&universe, 20.), Some(2));
+    assert_eq!(viewport.column_at(&universe, 44. + 32.), Some(6));
+
+    viewport.center_on(&universe);
+    assert_eq!(viewport.column_at(&universe, 50.), Some(15));
+    assert_eq!(viewport.row_at(&universe, 50.), Some(10));
+}